@@ -1,5 +1,7 @@
 pub mod expr;
-pub use expr::Expr;
+pub use expr::{Expr, ExprId, InterpPart, next_expr_id};
+
+pub mod stats;
 
 mod stmt;
 pub use stmt::Stmt;
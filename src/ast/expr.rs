@@ -1,12 +1,24 @@
 use crate::common::{Literal, Token};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Identifies a variable access, so later passes can attach data to it in a side table.
+pub type ExprId = usize;
+
+/// Returns an id that is unique across every tree parsed by this process,
+/// so side tables built for different programs can never mix up their entries.
+pub fn next_expr_id() -> ExprId {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
 
 /// Expression enum.
 /// An expression is something that produces a value
 /// For example:
 ///     1 + 1 = 2
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Expr {
     Assign {
+        id: ExprId,
         name: Token,
         value: Box<Expr>,
     },
@@ -15,9 +27,23 @@ pub enum Expr {
         operator: Token,
         right: Box<Expr>,
     },
+    Call {
+        callee: Box<Expr>,
+        /// The closing `)`, used to report errors for the call.
+        paren: Token,
+        arguments: Vec<Expr>,
+    },
+    /// Reads a property, e.g. `point.x`
+    Get {
+        object: Box<Expr>,
+        name: Token,
+    },
     Grouping {
         expression: Box<Expr>,
     },
+    Interpolation {
+        parts: Vec<InterpPart>,
+    },
     Literal {
         value: Literal,
     },
@@ -26,11 +52,30 @@ pub enum Expr {
         operator: Token,
         right: Box<Expr>,
     },
+    /// Writes a field, e.g. `point.x = 1`
+    Set {
+        object: Box<Expr>,
+        name: Token,
+        value: Box<Expr>,
+    },
+    /// The instance a method was called on, resolved like a variable
+    This {
+        id: ExprId,
+        keyword: Token,
+    },
     Unary {
         operator: Token,
         right: Box<Expr>,
     },
     Variable {
+        id: ExprId,
         name: Token,
     },
 }
+
+/// A piece of an interpolated string: either literal text or an embedded `${...}` expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InterpPart {
+    Literal(String),
+    Expr(Box<Expr>),
+}
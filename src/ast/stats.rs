@@ -0,0 +1,166 @@
+use crate::ast::{Expr, InterpPart, Stmt};
+use std::collections::HashMap;
+
+/// Counts how often each kind of node appears in the tree, keyed by names like `Expr::Binary`.
+pub fn count_nodes(statements: &[Stmt]) -> HashMap<&'static str, usize> {
+    let mut counts = HashMap::new();
+    for stmt in statements {
+        count_stmt(stmt, &mut counts);
+    }
+
+    counts
+}
+
+fn count_stmt(stmt: &Stmt, counts: &mut HashMap<&'static str, usize>) {
+    let name = match stmt {
+        Stmt::Assert { condition, .. } => {
+            count_expr(condition, counts);
+            "Stmt::Assert"
+        }
+        Stmt::Block { statements } => {
+            statements.iter().for_each(|s| count_stmt(s, counts));
+            "Stmt::Block"
+        }
+        Stmt::Class { methods, .. } => {
+            methods.iter().for_each(|s| count_stmt(s, counts));
+            "Stmt::Class"
+        }
+        Stmt::Expression { expression } => {
+            count_expr(expression, counts);
+            "Stmt::Expression"
+        }
+        Stmt::Function { body, .. } => {
+            body.iter().for_each(|s| count_stmt(s, counts));
+            "Stmt::Function"
+        }
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            count_expr(condition, counts);
+            count_stmt(then_branch, counts);
+            if let Some(else_branch) = else_branch {
+                count_stmt(else_branch, counts);
+            }
+            "Stmt::If"
+        }
+        Stmt::Print { expression } => {
+            count_expr(expression, counts);
+            "Stmt::Print"
+        }
+        Stmt::Printf {
+            format, arguments, ..
+        } => {
+            count_expr(format, counts);
+            arguments.iter().for_each(|e| count_expr(e, counts));
+            "Stmt::Printf"
+        }
+        Stmt::Return { value, .. } => {
+            if let Some(value) = value {
+                count_expr(value, counts);
+            }
+            "Stmt::Return"
+        }
+        Stmt::Var { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                count_expr(initializer, counts);
+            }
+            "Stmt::Var"
+        }
+        Stmt::While { condition, body } => {
+            count_expr(condition, counts);
+            count_stmt(body, counts);
+            "Stmt::While"
+        }
+    };
+
+    *counts.entry(name).or_insert(0) += 1;
+}
+
+fn count_expr(expr: &Expr, counts: &mut HashMap<&'static str, usize>) {
+    let name = match expr {
+        Expr::Assign { value, .. } => {
+            count_expr(value, counts);
+            "Expr::Assign"
+        }
+        Expr::Binary { left, right, .. } => {
+            count_expr(left, counts);
+            count_expr(right, counts);
+            "Expr::Binary"
+        }
+        Expr::Call {
+            callee, arguments, ..
+        } => {
+            count_expr(callee, counts);
+            arguments.iter().for_each(|e| count_expr(e, counts));
+            "Expr::Call"
+        }
+        Expr::Get { object, .. } => {
+            count_expr(object, counts);
+            "Expr::Get"
+        }
+        Expr::Grouping { expression } => {
+            count_expr(expression, counts);
+            "Expr::Grouping"
+        }
+        Expr::Interpolation { parts } => {
+            for part in parts {
+                if let InterpPart::Expr(expr) = part {
+                    count_expr(expr, counts);
+                }
+            }
+            "Expr::Interpolation"
+        }
+        Expr::Literal { .. } => "Expr::Literal",
+        Expr::Logical { left, right, .. } => {
+            count_expr(left, counts);
+            count_expr(right, counts);
+            "Expr::Logical"
+        }
+        Expr::Set { object, value, .. } => {
+            count_expr(object, counts);
+            count_expr(value, counts);
+            "Expr::Set"
+        }
+        Expr::This { .. } => "Expr::This",
+        Expr::Unary { right, .. } => {
+            count_expr(right, counts);
+            "Expr::Unary"
+        }
+        Expr::Variable { .. } => "Expr::Variable",
+    };
+
+    *counts.entry(name).or_insert(0) += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+    use crate::scanner::Scanner;
+
+    #[test]
+    fn count_nodes_by_variant() {
+        // Arrange
+        let source = "var a = 1 + 2 * 3;\nif (a > 1) { print a; } else print -a;";
+        let tokens = Scanner::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        // Act
+        let counts = count_nodes(&statements);
+
+        // Assert
+        let expected = HashMap::from([
+            ("Stmt::Var", 1),
+            ("Stmt::If", 1),
+            ("Stmt::Block", 1),
+            ("Stmt::Print", 2),
+            ("Expr::Binary", 3),
+            ("Expr::Literal", 4),
+            ("Expr::Variable", 3),
+            ("Expr::Unary", 1),
+        ]);
+        assert_eq!(counts, expected);
+    }
+}
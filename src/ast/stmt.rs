@@ -1,18 +1,37 @@
 use crate::ast::Expr;
 use crate::common::Token;
+use std::rc::Rc;
 
 /// Statement enum
 /// Statements DO something, not producing values
 /// For example:
 ///     print "Hello!";
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Stmt {
+    /// `assert condition;` fails at runtime when the condition is falsy
+    Assert {
+        keyword: Token,
+        condition: Box<Expr>,
+        /// The condition as written, for the failure message
+        source: String,
+    },
     Block {
         statements: Vec<Stmt>,
     },
+    Class {
+        name: Token,
+        /// Each method is a `Stmt::Function`
+        methods: Vec<Stmt>,
+    },
     Expression {
         expression: Box<Expr>,
     },
+    Function {
+        name: Token,
+        parameters: Vec<Token>,
+        /// Shared with every function value created from the declaration
+        body: Rc<[Stmt]>,
+    },
     If {
         condition: Box<Expr>,
         then_branch: Box<Stmt>,
@@ -21,6 +40,16 @@ pub enum Stmt {
     Print {
         expression: Box<Expr>,
     },
+    /// `printf "{} = {}", name, value;` fills each `{}` in the format with the next argument
+    Printf {
+        keyword: Token,
+        format: Box<Expr>,
+        arguments: Vec<Expr>,
+    },
+    Return {
+        keyword: Token,
+        value: Option<Box<Expr>>,
+    },
     Var {
         name: Token,
         initializer: Option<Box<Expr>>,
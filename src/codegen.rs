@@ -1,3 +1,12 @@
+mod class;
+mod counted_loop;
 mod environment;
+pub use environment::EnvSnapshot;
+mod function;
+mod gc;
+pub use function::Callable;
+
 pub mod interpreter;
+mod native;
+pub use native::{NATIVES, NativeFunction};
 mod runtime_error;
@@ -0,0 +1,150 @@
+use crate::codegen::environment::Environment;
+use crate::codegen::function::{Callable, Function};
+use crate::codegen::interpreter::{Interpreter, Value};
+use crate::codegen::runtime_error::RuntimeError;
+use crate::common::Token;
+use crate::common::error_context::ErrorContext;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+/// A class declared in Lox source with `class`, calling it constructs a new instance.
+pub struct LoxClass {
+    name: String,
+    methods: HashMap<String, Function>,
+    /// The class itself, so the instances it constructs can refer back to it.
+    this: Weak<LoxClass>,
+}
+
+impl LoxClass {
+    pub fn new(name: String, methods: HashMap<String, Function>) -> Rc<Self> {
+        Rc::new_cyclic(|this| Self {
+            name,
+            methods,
+            this: this.clone(),
+        })
+    }
+
+    pub fn find_method(&self, name: &str) -> Option<&Function> {
+        self.methods.get(name)
+    }
+}
+
+impl Callable for LoxClass {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Calling the class passes its arguments on to `init`.
+    fn arity(&self) -> usize {
+        self.find_method("init").map_or(0, Callable::arity)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        paren: &Token,
+    ) -> Result<Value, RuntimeError> {
+        let class = self
+            .this
+            .upgrade()
+            .expect("a class is alive while it is being called");
+        let instance = Value::Instance(Rc::new(RefCell::new(LoxInstance::new(class))));
+
+        if let Some(initializer) = self.find_method("init") {
+            initializer
+                .bind(instance.clone())
+                .call(interpreter, arguments, paren)?;
+        }
+
+        Ok(instance)
+    }
+
+    fn environments(&self) -> Vec<Rc<RefCell<Environment>>> {
+        self.methods
+            .values()
+            .flat_map(Callable::environments)
+            .collect()
+    }
+}
+
+impl fmt::Debug for LoxClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<class {}>", self.name)
+    }
+}
+
+/// Classes are compared by identity, like functions.
+impl PartialEq for LoxClass {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+/// An object constructed by calling a class, holding its own fields.
+pub struct LoxInstance {
+    class: Rc<LoxClass>,
+    fields: HashMap<String, Value>,
+}
+
+impl LoxInstance {
+    pub fn new(class: Rc<LoxClass>) -> Self {
+        Self {
+            class,
+            fields: HashMap::new(),
+        }
+    }
+
+    /// Reads a field, or else a method bound to `instance`. Fields shadow methods of the same name.
+    pub fn get(instance: &Rc<RefCell<Self>>, name: &Token) -> Result<Value, RuntimeError> {
+        let this = instance.borrow();
+        if let Some(value) = this.fields.get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        match this.class.find_method(&name.lexeme) {
+            Some(method) => Ok(Value::Callable(Rc::new(
+                method.bind(Value::Instance(instance.clone())),
+            ))),
+            None => Err(error(format!("undefined property '{}'", name.lexeme), name)),
+        }
+    }
+
+    pub fn set(&mut self, name: &Token, value: Value) {
+        self.fields.insert(name.lexeme.clone(), value);
+    }
+
+    pub fn class(&self) -> &Rc<LoxClass> {
+        &self.class
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = &Value> {
+        self.fields.values()
+    }
+}
+
+impl fmt::Debug for LoxInstance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{} instance>", self.class.name)
+    }
+}
+
+/// Instances are compared by identity, two instances with equal fields are still different values.
+impl PartialEq for LoxInstance {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+fn error(message: String, token: &Token) -> RuntimeError {
+    RuntimeError {
+        message,
+        context: ErrorContext {
+            line_number: token.line,
+            column: token.column,
+            lexeme: token.lexeme.clone(),
+        },
+    }
+}
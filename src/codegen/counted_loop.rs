@@ -0,0 +1,192 @@
+use crate::ast::{Expr, ExprId, InterpPart, Stmt};
+use crate::common::{Literal, Token, TokenType};
+
+/// A loop of the shape `while (i < bound) { ...; i = i + step; }`, which is also what
+/// a `for` loop with a numeric counter desugars into. As long as nothing else in the body
+/// touches the counter, it can be stepped as an integer instead of evaluating the
+/// condition and increment expressions on every iteration.
+pub struct CountedLoop<'a> {
+    pub counter: &'a Token,
+    /// The condition's access to the counter, which is resolved in the scope the loop runs in.
+    pub counter_id: ExprId,
+    pub comparison: TokenType,
+    pub bound: f64,
+    pub step: i64,
+    /// The loop body without the trailing increment.
+    pub body: &'a [Stmt],
+}
+
+impl<'a> CountedLoop<'a> {
+    /// Integers beyond this can't be represented exactly as `f64`.
+    pub const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+    pub fn detect(condition: &'a Expr, body: &'a Stmt) -> Option<Self> {
+        let Expr::Binary {
+            left,
+            operator,
+            right,
+        } = condition
+        else {
+            return None;
+        };
+
+        let (
+            Expr::Variable {
+                id: counter_id,
+                name: counter,
+            },
+            Expr::Literal { value },
+        ) = (&**left, &**right)
+        else {
+            return None;
+        };
+
+        let Literal::Number(bound) = value else {
+            return None;
+        };
+
+        if !matches!(
+            operator.token_type,
+            TokenType::Less | TokenType::LessEqual | TokenType::Greater | TokenType::GreaterEqual
+        ) || bound.abs() > Self::MAX_SAFE_INTEGER
+        {
+            return None;
+        }
+
+        let Stmt::Block { statements } = body else {
+            return None;
+        };
+
+        let (increment, body) = statements.split_last()?;
+        let step = increment_step(increment, counter)?;
+
+        if body.iter().any(|stmt| touches(stmt, counter)) {
+            return None;
+        }
+
+        Some(Self {
+            counter,
+            counter_id: *counter_id,
+            comparison: operator.token_type.clone(),
+            bound: *bound,
+            step,
+            body,
+        })
+    }
+
+    pub fn should_continue(&self, counter: i64) -> bool {
+        let counter = counter as f64;
+        match self.comparison {
+            TokenType::Less => counter < self.bound,
+            TokenType::LessEqual => counter <= self.bound,
+            TokenType::Greater => counter > self.bound,
+            TokenType::GreaterEqual => counter >= self.bound,
+            _ => unreachable!("counted loops only use comparison operators"),
+        }
+    }
+}
+
+/// Matches `counter = counter + step` or `counter = counter - step` with an integer step.
+fn increment_step(stmt: &Stmt, counter: &Token) -> Option<i64> {
+    let Stmt::Expression { expression } = stmt else {
+        return None;
+    };
+
+    let Expr::Assign { name, value, .. } = &**expression else {
+        return None;
+    };
+
+    let Expr::Binary {
+        left,
+        operator,
+        right,
+    } = &**value
+    else {
+        return None;
+    };
+
+    let (Expr::Variable { name: operand, .. }, Expr::Literal { value }) = (&**left, &**right)
+    else {
+        return None;
+    };
+
+    let Literal::Number(step) = value else {
+        return None;
+    };
+
+    if name.lexeme != counter.lexeme
+        || operand.lexeme != counter.lexeme
+        || step.fract() != 0.0
+        || step.abs() > CountedLoop::MAX_SAFE_INTEGER
+    {
+        return None;
+    }
+
+    match operator.token_type {
+        TokenType::Plus => Some(*step as i64),
+        TokenType::Minus => Some(-*step as i64),
+        _ => None,
+    }
+}
+
+/// Whether a statement might assign or shadow the counter.
+fn touches(stmt: &Stmt, counter: &Token) -> bool {
+    match stmt {
+        Stmt::Assert { condition, .. } => assigns(condition, counter),
+        Stmt::Block { statements } => statements.iter().any(|s| touches(s, counter)),
+        Stmt::Class { name, methods } => {
+            name.lexeme == counter.lexeme || methods.iter().any(|s| touches(s, counter))
+        }
+        Stmt::Expression { expression } | Stmt::Print { expression } => {
+            assigns(expression, counter)
+        }
+        Stmt::Printf {
+            format, arguments, ..
+        } => assigns(format, counter) || arguments.iter().any(|expr| assigns(expr, counter)),
+        Stmt::Function { name, body, .. } => {
+            name.lexeme == counter.lexeme || body.iter().any(|s| touches(s, counter))
+        }
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            assigns(condition, counter)
+                || touches(then_branch, counter)
+                || else_branch
+                    .as_ref()
+                    .is_some_and(|stmt| touches(stmt, counter))
+        }
+        Stmt::Return { value, .. } => value.as_ref().is_some_and(|expr| assigns(expr, counter)),
+        Stmt::Var { name, initializer } => {
+            name.lexeme == counter.lexeme
+                || initializer
+                    .as_ref()
+                    .is_some_and(|expr| assigns(expr, counter))
+        }
+        Stmt::While { condition, body } => assigns(condition, counter) || touches(body, counter),
+    }
+}
+
+fn assigns(expr: &Expr, counter: &Token) -> bool {
+    match expr {
+        Expr::Assign { name, value, .. } => {
+            name.lexeme == counter.lexeme || assigns(value, counter)
+        }
+        Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+            assigns(left, counter) || assigns(right, counter)
+        }
+        // The called function may assign the counter itself
+        Expr::Call { .. } => true,
+        Expr::Get { object, .. } => assigns(object, counter),
+        Expr::Grouping { expression } => assigns(expression, counter),
+        Expr::Interpolation { parts } => parts.iter().any(|part| match part {
+            InterpPart::Expr(expr) => assigns(expr, counter),
+            InterpPart::Literal(_) => false,
+        }),
+        // Setting a field never rebinds the counter itself
+        Expr::Set { object, value, .. } => assigns(object, counter) || assigns(value, counter),
+        Expr::Unary { right, .. } => assigns(right, counter),
+        Expr::Literal { .. } | Expr::This { .. } | Expr::Variable { .. } => false,
+    }
+}
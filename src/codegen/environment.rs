@@ -4,35 +4,113 @@ use crate::common::Token;
 use crate::common::error_context::ErrorContext;
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 #[derive(Clone)]
 pub struct Environment {
     values: HashMap<String, Value>,
     enclosing: Option<Rc<RefCell<Environment>>>,
+    /// Shared by every environment descending from the same global scope.
+    heap: Rc<RefCell<Heap>>,
+}
+
+/// Tracks the environments descending from one global scope, so the garbage collector
+/// can find the ones that are only kept alive by reference cycles.
+#[derive(Default)]
+struct Heap {
+    environments: Vec<Weak<RefCell<Environment>>>,
+    /// Dropped environments are pruned from the list once it grows to this length.
+    prune_at: usize,
+}
+
+impl Heap {
+    const MIN_PRUNE_AT: usize = 64;
+
+    fn track(&mut self, environment: &Rc<RefCell<Environment>>) {
+        // Pruning at double the live count keeps tracking amortized constant time
+        if self.environments.len() >= self.prune_at {
+            self.environments
+                .retain(|environment| environment.strong_count() > 0);
+            self.prune_at = (self.environments.len() * 2).max(Self::MIN_PRUNE_AT);
+        }
+
+        self.environments.push(Rc::downgrade(environment));
+    }
+}
+
+/// A copy of an environment's bindings that can later be restored.
+/// Rebinding or redefining a variable after the snapshot is taken does not affect it.
+pub struct EnvSnapshot {
+    values: HashMap<String, Value>,
 }
 
 impl Environment {
     const UNDEFINED_VARIABLE: &'static str = "undefined variable";
 
     pub fn new() -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(Self {
+        Self::allocate(Self {
             values: HashMap::new(),
             enclosing: None,
-        }))
+            heap: Rc::default(),
+        })
     }
 
     pub fn with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(Self {
+        let heap = enclosing.borrow().heap.clone();
+
+        Self::allocate(Self {
             values: HashMap::new(),
             enclosing: Some(enclosing),
-        }))
+            heap,
+        })
+    }
+
+    fn allocate(environment: Self) -> Rc<RefCell<Self>> {
+        let heap = environment.heap.clone();
+        let environment = Rc::new(RefCell::new(environment));
+        heap.borrow_mut().track(&environment);
+        environment
+    }
+
+    /// Every environment still alive that descends from the same global scope as `environment`.
+    pub fn live(environment: &Rc<RefCell<Self>>) -> Vec<Rc<RefCell<Self>>> {
+        let heap = environment.borrow().heap.clone();
+        let heap = heap.borrow();
+        heap.environments.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.values.values()
+    }
+
+    pub fn enclosing(&self) -> Option<&Rc<RefCell<Self>>> {
+        self.enclosing.as_ref()
+    }
+
+    /// Drops every variable, releasing whatever they kept alive.
+    pub fn clear(&mut self) {
+        self.values.clear();
     }
 
     pub fn define(&mut self, name: String, value: Value) {
         self.values.insert(name, value);
     }
 
+    pub fn snapshot(&self) -> EnvSnapshot {
+        EnvSnapshot {
+            values: self.values.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: EnvSnapshot) {
+        self.values = snapshot.values;
+    }
+
+    /// Reads a variable defined directly in this scope, e.g. the `this` of a bound method.
+    pub fn get_local(&self, name: &str) -> Option<Value> {
+        self.values.get(name).cloned()
+    }
+
     pub fn get_value(&self, name: &Token) -> Result<Value, RuntimeError> {
         if let Some(value) = self.values.get(&name.lexeme) {
             Ok(value.clone())
@@ -43,6 +121,61 @@ impl Environment {
         }
     }
 
+    /// Walks `depth` scopes up the chain that starts at `environment`.
+    pub fn ancestor(environment: &Rc<RefCell<Self>>, depth: usize) -> Rc<RefCell<Self>> {
+        let mut environment = environment.clone();
+        for _ in 0..depth {
+            let enclosing = environment.borrow().enclosing.clone();
+            environment = enclosing.expect("resolved depth exceeds the scope chain");
+        }
+
+        environment
+    }
+
+    /// Reads a variable from the scope `depth` levels up, as determined by the resolver.
+    /// Unlike `get_value`, no other scope is searched.
+    pub fn get_at(&self, depth: usize, name: &Token) -> Result<Value, RuntimeError> {
+        if depth > 0 {
+            return Self::ancestor(self.parent(), depth - 1)
+                .borrow()
+                .get_at(0, name);
+        }
+
+        self.values
+            .get(&name.lexeme)
+            .cloned()
+            .ok_or_else(|| error(Self::UNDEFINED_VARIABLE.into(), name.clone()))
+    }
+
+    /// Assigns a variable in the scope `depth` levels up, as determined by the resolver.
+    /// Unlike `assign`, no other scope is searched.
+    pub fn assign_at(
+        &mut self,
+        depth: usize,
+        name: &Token,
+        value: Value,
+    ) -> Result<(), RuntimeError> {
+        if depth > 0 {
+            return Self::ancestor(self.parent(), depth - 1)
+                .borrow_mut()
+                .assign_at(0, name, value);
+        }
+
+        match self.values.get_mut(&name.lexeme) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(error(Self::UNDEFINED_VARIABLE.into(), name.clone())),
+        }
+    }
+
+    fn parent(&self) -> &Rc<RefCell<Self>> {
+        self.enclosing
+            .as_ref()
+            .expect("resolved depth exceeds the scope chain")
+    }
+
     pub fn assign(&mut self, name: &Token, value: Value) -> Result<(), RuntimeError> {
         if self.values.contains_key(&name.lexeme) {
             self.values.insert(name.lexeme.clone(), value);
@@ -60,7 +193,127 @@ fn error(message: String, token: Token) -> RuntimeError {
         message,
         context: ErrorContext {
             line_number: token.line,
+            column: token.column,
             lexeme: token.lexeme,
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::TokenType;
+
+    fn name(lexeme: &str) -> Token {
+        Token {
+            token_type: TokenType::Identifier,
+            lexeme: lexeme.into(),
+            literal: None,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Builds global -> middle -> inner, each defining `a` as its own depth from `inner`.
+    fn three_levels() -> [Rc<RefCell<Environment>>; 3] {
+        let global = Environment::new();
+        let middle = Environment::with_enclosing(global.clone());
+        let inner = Environment::with_enclosing(middle.clone());
+
+        for (depth, environment) in [&inner, &middle, &global].into_iter().enumerate() {
+            environment
+                .borrow_mut()
+                .define("a".into(), Value::Number(depth as f64));
+        }
+
+        [inner, middle, global]
+    }
+
+    #[test]
+    fn ancestor_walks_up_the_chain() {
+        // Arrange
+        let levels = three_levels();
+
+        // Act
+        let ancestors: Vec<_> = (0..3)
+            .map(|depth| Environment::ancestor(&levels[0], depth))
+            .collect();
+
+        // Assert
+        for (ancestor, level) in ancestors.iter().zip(&levels) {
+            assert!(Rc::ptr_eq(ancestor, level));
+        }
+    }
+
+    #[test]
+    fn get_at_reads_each_depth() {
+        // Arrange
+        let [inner, ..] = three_levels();
+
+        // Act
+        let values: Vec<Value> = (0..3)
+            .map(|depth| inner.borrow().get_at(depth, &name("a")).unwrap())
+            .collect();
+
+        // Assert
+        assert_eq!(
+            values,
+            [Value::Number(0.0), Value::Number(1.0), Value::Number(2.0)]
+        );
+    }
+
+    #[test]
+    fn assign_at_writes_only_the_given_depth() {
+        // Arrange
+        let [inner, middle, global] = three_levels();
+
+        // Act
+        inner
+            .borrow_mut()
+            .assign_at(1, &name("a"), Value::Number(10.0))
+            .unwrap();
+
+        // Assert
+        assert_eq!(
+            inner.borrow().get_at(0, &name("a")).unwrap(),
+            Value::Number(0.0)
+        );
+        assert_eq!(
+            middle.borrow().get_at(0, &name("a")).unwrap(),
+            Value::Number(10.0)
+        );
+        assert_eq!(
+            global.borrow().get_at(0, &name("a")).unwrap(),
+            Value::Number(2.0)
+        );
+    }
+
+    #[test]
+    fn get_at_does_not_search_other_scopes() {
+        // Arrange
+        let [inner, _, global] = three_levels();
+        global.borrow_mut().define("b".into(), Value::Boolean(true));
+
+        // Act
+        let result = inner.borrow().get_at(1, &name("b"));
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn live_tracks_only_environments_still_alive() {
+        // Arrange
+        let global = Environment::new();
+        let kept = Environment::with_enclosing(global.clone());
+
+        // Act
+        for _ in 0..100 {
+            Environment::with_enclosing(kept.clone());
+        }
+
+        // Assert
+        assert_eq!(Environment::live(&global).len(), 2);
+        assert!(global.borrow().heap.borrow().environments.len() < 100);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::ast::Stmt;
+use crate::codegen::environment::Environment;
+use crate::codegen::interpreter::{Interpreter, Value};
+use crate::codegen::runtime_error::{RuntimeError, Unwind};
+use crate::common::Token;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A value that can be called with a fixed number of arguments.
+pub trait Callable {
+    fn name(&self) -> &str;
+    fn arity(&self) -> usize;
+    /// `paren` is the closing parenthesis of the call, where errors raised by the call itself are reported.
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        paren: &Token,
+    ) -> Result<Value, RuntimeError>;
+
+    /// The environments the callable keeps alive, which the garbage collector has to trace.
+    fn environments(&self) -> Vec<Rc<RefCell<Environment>>> {
+        vec![]
+    }
+}
+
+impl fmt::Debug for dyn Callable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<fn {}>", self.name())
+    }
+}
+
+/// Callables are compared by identity, two declarations of the same function are different values.
+impl PartialEq for dyn Callable {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+}
+
+/// A function declared in Lox source with `fun`.
+#[derive(Clone)]
+pub struct Function {
+    name: Token,
+    parameters: Vec<Token>,
+    /// Shared with every bound copy of the method.
+    body: Rc<[Stmt]>,
+    /// The scope the function was declared in, which its body can keep referring to.
+    closure: Rc<RefCell<Environment>>,
+    /// Whether this is a class's `init` method, which always returns its instance.
+    is_initializer: bool,
+}
+
+impl Function {
+    pub fn new(
+        name: Token,
+        parameters: Vec<Token>,
+        body: Rc<[Stmt]>,
+        closure: Rc<RefCell<Environment>>,
+    ) -> Self {
+        Self {
+            name,
+            parameters,
+            body,
+            closure,
+            is_initializer: false,
+        }
+    }
+
+    /// Marks the function as a class's `init` method.
+    pub fn initializer(mut self) -> Self {
+        self.is_initializer = true;
+        self
+    }
+
+    /// Returns a copy of the method whose body sees `instance` as `this`.
+    pub fn bind(&self, instance: Value) -> Function {
+        let environment = Environment::with_enclosing(self.closure.clone());
+        environment.borrow_mut().define("this".into(), instance);
+
+        Function {
+            closure: environment,
+            ..self.clone()
+        }
+    }
+}
+
+impl Callable for Function {
+    fn name(&self) -> &str {
+        &self.name.lexeme
+    }
+
+    fn arity(&self) -> usize {
+        self.parameters.len()
+    }
+
+    fn environments(&self) -> Vec<Rc<RefCell<Environment>>> {
+        vec![self.closure.clone()]
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        _: &Token,
+    ) -> Result<Value, RuntimeError> {
+        let environment = Environment::with_enclosing(self.closure.clone());
+
+        for (parameter, argument) in self.parameters.iter().zip(arguments) {
+            environment
+                .borrow_mut()
+                .define(parameter.lexeme.clone(), argument);
+        }
+
+        let result = interpreter.execute_block(&self.body, environment);
+
+        // The resolver only allows a bare `return;` in an initializer
+        if self.is_initializer && !matches!(result, Err(Unwind::Error(_))) {
+            return Ok(self
+                .closure
+                .borrow()
+                .get_local("this")
+                .expect("an initializer is bound to its instance"));
+        }
+
+        match result {
+            Ok(()) => Ok(Value::Nil),
+            Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Error(error)) => Err(error),
+        }
+    }
+}
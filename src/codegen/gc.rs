@@ -0,0 +1,103 @@
+use crate::codegen::class::LoxInstance;
+use crate::codegen::environment::Environment;
+use crate::codegen::function::Callable;
+use crate::codegen::interpreter::Value;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Reclaims environments that are only kept alive by reference cycles, such as a closure stored
+/// in the scope it captured. Everything reachable from `roots` is kept, every other environment
+/// descending from the same global scope has its variables dropped, which breaks the cycles.
+/// Returns how many environments were reclaimed.
+///
+/// Values held anywhere but the roots, e.g. by Rust code in the middle of evaluating an expression,
+/// are not traced, so this must only run when the roots are all that is in use.
+pub fn collect_garbage(roots: &[Rc<RefCell<Environment>>]) -> usize {
+    let Some(root) = roots.first() else {
+        return 0;
+    };
+
+    let mut marker = Marker::default();
+    for root in roots {
+        marker.trace(root);
+    }
+
+    let unreachable: Vec<_> = Environment::live(root)
+        .into_iter()
+        .filter(|environment| !marker.environments.contains(&Rc::as_ptr(environment)))
+        .collect();
+
+    for environment in &unreachable {
+        environment.borrow_mut().clear();
+    }
+
+    unreachable.len()
+}
+
+/// Remembers everything found reachable so far, by address. Whatever still has to be traced is kept
+/// in a worklist rather than on the call stack, so long chains of values can't overflow it.
+#[derive(Default)]
+struct Marker {
+    environments: HashSet<*const RefCell<Environment>>,
+    instances: HashSet<*const RefCell<LoxInstance>>,
+    pending_environments: Vec<Rc<RefCell<Environment>>>,
+    pending_instances: Vec<Rc<RefCell<LoxInstance>>>,
+}
+
+impl Marker {
+    /// Marks everything reachable from the environment.
+    fn trace(&mut self, environment: &Rc<RefCell<Environment>>) {
+        self.mark_environment(environment);
+
+        loop {
+            if let Some(environment) = self.pending_environments.pop() {
+                let environment = environment.borrow();
+                for value in environment.values() {
+                    self.mark_value(value);
+                }
+
+                if let Some(enclosing) = environment.enclosing() {
+                    self.mark_environment(enclosing);
+                }
+            } else if let Some(instance) = self.pending_instances.pop() {
+                let instance = instance.borrow();
+                for environment in instance.class().environments() {
+                    self.mark_environment(&environment);
+                }
+                for value in instance.fields() {
+                    self.mark_value(value);
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn mark_environment(&mut self, environment: &Rc<RefCell<Environment>>) {
+        if self.environments.insert(Rc::as_ptr(environment)) {
+            self.pending_environments.push(environment.clone());
+        }
+    }
+
+    fn mark_value(&mut self, value: &Value) {
+        match value {
+            Value::Callable(callable) => {
+                for environment in callable.environments() {
+                    self.mark_environment(&environment);
+                }
+            }
+            Value::Class(class) => {
+                for environment in class.environments() {
+                    self.mark_environment(&environment);
+                }
+            }
+            Value::Instance(instance) => {
+                if self.instances.insert(Rc::as_ptr(instance)) {
+                    self.pending_instances.push(instance.clone());
+                }
+            }
+            Value::Number(_) | Value::String(_) | Value::Boolean(_) | Value::Nil => {}
+        }
+    }
+}
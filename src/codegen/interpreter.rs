@@ -1,11 +1,20 @@
-use crate::ast::{Expr, Stmt};
-use crate::codegen::environment::Environment;
-use crate::codegen::runtime_error::RuntimeError;
+use crate::ast::{Expr, ExprId, InterpPart, Stmt};
+use crate::codegen::class::{LoxClass, LoxInstance};
+use crate::codegen::counted_loop::CountedLoop;
+use crate::codegen::environment::{EnvSnapshot, Environment};
+use crate::codegen::function::{Callable, Function};
+use crate::codegen::gc;
+use crate::codegen::native::NATIVES;
+use crate::codegen::runtime_error::{RuntimeError, Unwind};
 use crate::common::TokenType;
 use crate::common::error_context::ErrorContext;
 use crate::common::{Literal, Token};
+use crate::semantics::resolver::{Binding, Locals};
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt;
+use std::io::{self, Write};
 use std::rc::Rc;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -14,6 +23,9 @@ pub enum Value {
     String(String),
     Boolean(bool),
     Nil,
+    Callable(Rc<dyn Callable>),
+    Class(Rc<LoxClass>),
+    Instance(Rc<RefCell<LoxInstance>>),
 }
 
 impl fmt::Display for Value {
@@ -23,49 +35,338 @@ impl fmt::Display for Value {
             Value::String(s) => write!(f, "{}", s),
             Value::Boolean(b) => write!(f, "{}", b),
             Value::Nil => write!(f, "nil"),
+            Value::Callable(callable) => write!(f, "<fn {}>", callable.name()),
+            Value::Class(class) => write!(f, "{:?}", class),
+            Value::Instance(instance) => write!(f, "{:?}", instance.borrow()),
         }
     }
 }
 
+impl Value {
+    /// The name of the value's type as shown to Lox users, e.g. in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Boolean(_) => "boolean",
+            Value::Nil => "nil",
+            Value::Callable(_) => "function",
+            Value::Class(_) => "class",
+            Value::Instance(_) => "instance",
+        }
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Boolean(b)
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(n) => Ok(n),
+            other => Err(type_mismatch("number", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => Ok(s),
+            other => Err(type_mismatch("string", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Boolean(b) => Ok(b),
+            other => Err(type_mismatch("boolean", &other)),
+        }
+    }
+}
+
+fn type_mismatch(expected: &str, actual: &Value) -> String {
+    format!("expected a {} but got {}", expected, actual.type_name())
+}
+
 pub struct Interpreter {
     statements: Vec<Stmt>,
+    /// The outermost scope, where top-level declarations live.
+    pub(crate) globals: Rc<RefCell<Environment>>,
     environment: Rc<RefCell<Environment>>,
+    /// Where each resolved variable access finds its variable, unresolved ones are looked up dynamically.
+    locals: Locals,
+    output: Box<dyn Write>,
+    precision: Option<usize>,
+    safe_math: bool,
+    /// How often each function was called, keyed by name. Only tracked when profiling is enabled.
+    call_counts: Option<HashMap<String, usize>>,
+    /// Set by the `gc()` native, collecting is deferred to the end of the top-level statement.
+    gc_requested: bool,
 }
 
 impl Interpreter {
     pub fn new(stmts: Vec<Stmt>) -> Self {
+        let globals = new_globals();
+
         Interpreter {
             statements: stmts,
-            environment: Environment::new(),
+            environment: globals.clone(),
+            globals,
+            locals: Locals::new(),
+            output: Box::new(io::stdout()),
+            precision: None,
+            safe_math: false,
+            call_counts: None,
+            gc_requested: false,
         }
     }
 
+    /// Uses the bindings the `Resolver` found for the statements, so closures keep referring
+    /// to the variables in scope where they were declared. Without them every variable is looked up
+    /// in the scopes active when the access runs.
+    pub fn with_locals(mut self, locals: Locals) -> Self {
+        self.locals = locals;
+        self
+    }
+
+    /// Redirects the output of `print` statements, e.g. to `io::sink()` to discard it.
+    pub fn with_output(mut self, output: impl Write + 'static) -> Self {
+        self.output = Box::new(output);
+        self
+    }
+
+    /// Limits printed numbers to the given amount of significant digits, at least one.
+    pub fn with_precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+
+    /// Makes arithmetic on finite numbers raise an error when the result is infinite or NaN.
+    pub fn with_safe_math(mut self) -> Self {
+        self.safe_math = true;
+        self
+    }
+
+    /// Counts the calls made to each function, see `call_counts`.
+    pub fn with_call_profiling(mut self) -> Self {
+        self.call_counts = Some(HashMap::new());
+        self
+    }
+
+    /// The number of calls per function name, if call profiling is enabled.
+    pub fn call_counts(&self) -> Option<&HashMap<String, usize>> {
+        self.call_counts.as_ref()
+    }
+
+    /// Writes a value on its own line to the output, as formatted for the user.
+    pub(crate) fn print(&mut self, value: &Value) {
+        let text = self.to_display_string(value);
+        self.write_line(&text);
+    }
+
+    fn write_line(&mut self, text: &str) {
+        writeln!(self.output, "{}", text).expect("failed to write output");
+    }
+
+    /// Schedules a garbage collection for when the current top-level statement completes.
+    pub(crate) fn request_gc(&mut self) {
+        self.gc_requested = true;
+    }
+
+    /// Reclaims environments only kept alive by reference cycles, returning how many there were.
+    /// Values held outside the interpreter, like the bindings of an `EnvSnapshot`, are not kept alive,
+    /// so closures they refer to lose their captured variables.
+    pub fn collect_garbage(&mut self) -> usize {
+        gc::collect_garbage(&[self.globals.clone(), self.environment.clone()])
+    }
+
+    /// The number of environments currently allocated, including ones only kept alive by cycles.
+    pub fn live_environments(&self) -> usize {
+        Environment::live(&self.globals).len()
+    }
+
+    /// Drops every user definition so the interpreter can be reused for a fresh program.
+    pub fn reset(&mut self) {
+        self.globals = new_globals();
+        self.environment = self.globals.clone();
+    }
+
+    /// Captures the current global bindings, e.g. for a REPL "undo".
+    pub fn snapshot(&self) -> EnvSnapshot {
+        self.globals.borrow().snapshot()
+    }
+
+    /// Rolls the global bindings back to a previously taken snapshot.
+    pub fn restore(&mut self, snapshot: EnvSnapshot) {
+        self.globals.borrow_mut().restore(snapshot);
+    }
+
     pub fn interpret(&mut self) -> Result<(), RuntimeError> {
         let stmts = std::mem::take(&mut self.statements);
         for stmt in stmts {
             match self.execute(&stmt) {
+                // Between top-level statements every value in use is reachable from the globals
+                Ok(_) if std::mem::take(&mut self.gc_requested) => {
+                    self.collect_garbage();
+                }
                 Ok(_) => {}
-                Err(e) => {
+                Err(Unwind::Error(e)) => {
                     eprintln!("{}", e);
                     return Err(e);
                 }
+                Err(Unwind::Return(_)) => {
+                    unreachable!("the parser rejects return outside of functions")
+                }
             };
         }
 
         Ok(())
     }
 
-    pub fn execute(&mut self, stmt: &Stmt) -> Result<(), RuntimeError> {
+    /// Variables that weren't resolved, see `with_locals`, are looked up
+    /// in the scopes active when the access runs.
+    pub fn execute(&mut self, stmt: &Stmt) -> Result<(), Unwind> {
         match stmt {
+            Stmt::Assert {
+                keyword,
+                condition,
+                source,
+            } => {
+                if !is_truthy(&self.evaluate(condition)?) {
+                    return Err(error(format!("assertion failed: {}", source), keyword).into());
+                }
+
+                Ok(())
+            }
             Stmt::Expression { expression: expr } => {
                 self.evaluate(expr)?;
                 Ok(())
             }
             Stmt::Print { expression: expr } => {
                 let value = self.evaluate(expr)?;
-                println!("{}", value);
+                self.print(&value);
+                Ok(())
+            }
+            Stmt::Printf {
+                keyword,
+                format,
+                arguments,
+            } => {
+                let Value::String(format) = self.evaluate(format)? else {
+                    return Err(error("printf format must be a string".into(), keyword).into());
+                };
+
+                let placeholders = format.matches("{}").count();
+                if placeholders != arguments.len() {
+                    return Err(error(
+                        format!(
+                            "format expects {} arguments but got {}",
+                            placeholders,
+                            arguments.len()
+                        ),
+                        keyword,
+                    )
+                    .into());
+                }
+
+                let mut pieces = format.split("{}");
+                let mut text = pieces.next().unwrap_or_default().to_string();
+                for (argument, piece) in arguments.iter().zip(pieces) {
+                    let value = self.evaluate(argument)?;
+                    text.push_str(&self.to_display_string(&value));
+                    text.push_str(piece);
+                }
+
+                self.write_line(&text);
+                Ok(())
+            }
+            Stmt::Class { name, methods } => {
+                let methods = methods
+                    .iter()
+                    .filter_map(|method| match method {
+                        Stmt::Function {
+                            name,
+                            parameters,
+                            body,
+                        } => {
+                            let method = Function::new(
+                                name.clone(),
+                                parameters.clone(),
+                                body.clone(),
+                                self.environment.clone(),
+                            );
+                            let method = if name.lexeme == "init" {
+                                method.initializer()
+                            } else {
+                                method
+                            };
+
+                            Some((name.lexeme.clone(), method))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+
+                let class = LoxClass::new(name.lexeme.clone(), methods);
+                self.environment
+                    .borrow_mut()
+                    .define(name.lexeme.clone(), Value::Class(class));
                 Ok(())
             }
+            Stmt::Function {
+                name,
+                parameters,
+                body,
+            } => {
+                let function = Function::new(
+                    name.clone(),
+                    parameters.clone(),
+                    body.clone(),
+                    self.environment.clone(),
+                );
+                self.environment
+                    .borrow_mut()
+                    .define(name.lexeme.clone(), Value::Callable(Rc::new(function)));
+                Ok(())
+            }
+            Stmt::Return { value, .. } => {
+                let value = match value {
+                    Some(expr) => self.evaluate(expr)?,
+                    None => Value::Nil,
+                };
+
+                Err(Unwind::Return(value))
+            }
             Stmt::Var { name, initializer } => {
                 let value = if let Some(expr) = initializer {
                     self.evaluate(expr)?
@@ -80,8 +381,8 @@ impl Interpreter {
                 Ok(())
             }
             Stmt::Block { statements } => {
-                let new_env = Environment::with_enclosing(self.environment.clone());
-                self.execute_block(statements, Environment::with_enclosing(new_env))
+                let block_env = Environment::with_enclosing(self.environment.clone());
+                self.execute_block(statements, block_env)
             }
             Stmt::If {
                 condition,
@@ -97,6 +398,12 @@ impl Interpreter {
                 Ok(())
             }
             Stmt::While { condition, body } => {
+                if let Some(counted_loop) = CountedLoop::detect(condition, body) {
+                    if let Some(start) = self.integer_counter(&counted_loop) {
+                        return self.execute_counted_loop(&counted_loop, start);
+                    }
+                }
+
                 while is_truthy(&self.evaluate(condition.as_ref())?) {
                     self.execute(body)?;
                 }
@@ -108,13 +415,16 @@ impl Interpreter {
 
     pub fn evaluate(&mut self, expr: &Expr) -> Result<Value, RuntimeError> {
         match expr {
-            Expr::Variable { name } => self.environment.borrow().get_value(name),
-            Expr::Assign { name, value } => {
+            Expr::Variable { id, name } | Expr::This { id, keyword: name } => {
+                self.look_up_variable(*id, name)
+            }
+            Expr::Assign { id, name, value } => {
                 let val = self.evaluate(value)?;
-                self.environment.borrow_mut().assign(name, val.clone())?;
+                self.assign_variable(*id, name, val.clone())?;
                 Ok(val)
             }
             Expr::Literal { value } => match value {
+                Literal::Integer(n) => Ok(Value::Number(*n as f64)),
                 Literal::Number(n) => Ok(Value::Number(*n)),
                 Literal::String(s) => Ok(Value::String(s.clone())),
                 Literal::Boolean(b) => Ok(Value::Boolean(*b)),
@@ -123,6 +433,75 @@ impl Interpreter {
 
             Expr::Grouping { expression } => self.evaluate(expression),
 
+            Expr::Get { object, name } => match self.evaluate(object)? {
+                Value::Instance(instance) => LoxInstance::get(&instance, name),
+                _ => Err(error("only instances have properties".into(), name)),
+            },
+
+            Expr::Set {
+                object,
+                name,
+                value,
+            } => {
+                let Value::Instance(instance) = self.evaluate(object)? else {
+                    return Err(error("only instances have fields".into(), name));
+                };
+
+                let value = self.evaluate(value)?;
+                instance.borrow_mut().set(name, value.clone());
+                Ok(value)
+            }
+
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+            } => {
+                let callee = self.evaluate(callee)?;
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.evaluate(argument))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let function: Rc<dyn Callable> = match callee {
+                    Value::Callable(function) => function,
+                    Value::Class(class) => class,
+                    _ => return Err(error("can only call functions and classes".into(), paren)),
+                };
+
+                if arguments.len() != function.arity() {
+                    return Err(error(
+                        format!(
+                            "expected {} arguments but got {}",
+                            function.arity(),
+                            arguments.len()
+                        ),
+                        paren,
+                    ));
+                }
+
+                if let Some(call_counts) = &mut self.call_counts {
+                    *call_counts.entry(function.name().to_string()).or_insert(0) += 1;
+                }
+
+                function.call(self, arguments, paren)
+            }
+
+            Expr::Interpolation { parts } => {
+                let mut result = String::new();
+                for part in parts {
+                    match part {
+                        InterpPart::Literal(text) => result.push_str(text),
+                        InterpPart::Expr(expr) => {
+                            let value = self.evaluate(expr)?;
+                            result.push_str(&self.to_display_string(&value))
+                        }
+                    }
+                }
+
+                Ok(Value::String(result))
+            }
+
             Expr::Unary { operator, right } => {
                 let right_val = self.evaluate(right)?;
                 match operator.token_type {
@@ -141,14 +520,22 @@ impl Interpreter {
             } => {
                 let left_val = self.evaluate(left)?;
                 let right_val = self.evaluate(right)?;
+                let finite_operands = is_finite(&left_val) && is_finite(&right_val);
 
-                match operator.token_type {
+                let result = match operator.token_type {
                     TokenType::Plus => match (left_val, right_val) {
                         (Value::Number(x), Value::Number(y)) => Ok(Value::Number(x + y)),
                         (Value::String(x), Value::String(y)) => {
                             Ok(Value::String(format!("{}{}", x, y)))
                         }
-                        _ => Err(error("Operator token type mismatch".into(), operator)),
+                        (x, y) => Err(error(
+                            format!(
+                                "operands must be two numbers or two strings, got {} and {}",
+                                x.type_name(),
+                                y.type_name()
+                            ),
+                            operator,
+                        )),
                     },
                     TokenType::Minus => num_bin_op(left_val, right_val, |x, y| x - y)
                         .map_err(|msg| error(msg, operator)),
@@ -159,21 +546,41 @@ impl Interpreter {
                     TokenType::Slash => num_bin_op(left_val, right_val, |x, y| x / y)
                         .map_err(|msg| error(msg, operator)),
 
-                    TokenType::Greater => bool_bin_op(left_val, right_val, |x, y| x > y)
+                    TokenType::Percent => num_bin_op(left_val, right_val, |x, y| x % y)
+                        .map_err(|msg| error(msg, operator)),
+
+                    TokenType::Greater => bool_bin_op(left_val, right_val, Ordering::is_gt)
                         .map_err(|msg| error(msg, operator)),
-                    TokenType::GreaterEqual => bool_bin_op(left_val, right_val, |x, y| x >= y)
+                    TokenType::GreaterEqual => bool_bin_op(left_val, right_val, Ordering::is_ge)
                         .map_err(|msg| error(msg, operator)),
 
-                    TokenType::Less => bool_bin_op(left_val, right_val, |x, y| x < y)
+                    TokenType::Less => bool_bin_op(left_val, right_val, Ordering::is_lt)
                         .map_err(|msg| error(msg, operator)),
-                    TokenType::LessEqual => bool_bin_op(left_val, right_val, |x, y| x <= y)
+                    TokenType::LessEqual => bool_bin_op(left_val, right_val, Ordering::is_le)
                         .map_err(|msg| error(msg, operator)),
 
                     TokenType::EqualEqual => Ok(Value::Boolean(left_val == right_val)),
                     TokenType::BangEqual => Ok(Value::Boolean(left_val != right_val)),
 
+                    TokenType::EqualEqualEqual => {
+                        Ok(Value::Boolean(strict_equals(&left_val, &right_val)))
+                    }
+                    TokenType::BangEqualEqual => {
+                        Ok(Value::Boolean(!strict_equals(&left_val, &right_val)))
+                    }
+
                     _ => Err(error("Operator token type mismatch".into(), operator)),
+                }?;
+
+                // In safe math mode, overflowing to infinity or NaN is an error instead of IEEE behavior
+                if self.safe_math
+                    && finite_operands
+                    && matches!(result, Value::Number(n) if !n.is_finite())
+                {
+                    return Err(error("numeric overflow".into(), operator));
                 }
+
+                Ok(result)
             }
             Expr::Logical {
                 left,
@@ -183,15 +590,14 @@ impl Interpreter {
                 let left_val = self.evaluate(left)?;
 
                 match operator.token_type {
-                    TokenType::Or => {
-                        if is_truthy(&left_val) {
-                            return Ok(left_val);
-                        }
-                    }
-                    TokenType::And => {
-                        if !is_truthy(&left_val) {
-                            return Ok(left_val);
-                        }
+                    TokenType::Or if is_truthy(&left_val) => return Ok(left_val),
+                    TokenType::And if !is_truthy(&left_val) => return Ok(left_val),
+                    // `xor` never short-circuits, both operands decide the result
+                    TokenType::Xor => {
+                        let right_val = self.evaluate(right)?;
+                        return Ok(Value::Boolean(
+                            is_truthy(&left_val) != is_truthy(&right_val),
+                        ));
                     }
                     _ => {}
                 }
@@ -202,11 +608,77 @@ impl Interpreter {
         }
     }
 
+    fn look_up_variable(&self, id: ExprId, name: &Token) -> Result<Value, RuntimeError> {
+        match self.locals.get(&id) {
+            Some(&Binding::Local(depth)) => self.environment.borrow().get_at(depth, name),
+            Some(Binding::Global) => self.globals.borrow().get_value(name),
+            None => self.environment.borrow().get_value(name),
+        }
+    }
+
+    fn assign_variable(&self, id: ExprId, name: &Token, value: Value) -> Result<(), RuntimeError> {
+        match self.locals.get(&id) {
+            Some(&Binding::Local(depth)) => {
+                self.environment.borrow_mut().assign_at(depth, name, value)
+            }
+            Some(Binding::Global) => self.globals.borrow_mut().assign(name, value),
+            None => self.environment.borrow_mut().assign(name, value),
+        }
+    }
+
+    /// The counter's current value, if it can be stepped as an integer without losing precision.
+    fn integer_counter(&self, counted_loop: &CountedLoop) -> Option<i64> {
+        match self.look_up_variable(counted_loop.counter_id, counted_loop.counter) {
+            Ok(Value::Number(n))
+                if n.fract() == 0.0 && n.abs() <= CountedLoop::MAX_SAFE_INTEGER =>
+            {
+                Some(n as i64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Fast path for `CountedLoop`, observably identical to evaluating its condition and increment.
+    fn execute_counted_loop(
+        &mut self,
+        counted_loop: &CountedLoop,
+        start: i64,
+    ) -> Result<(), Unwind> {
+        let mut counter = start;
+
+        while counted_loop.should_continue(counter) {
+            let body_env = Environment::with_enclosing(self.environment.clone());
+            self.execute_block(counted_loop.body, body_env)?;
+
+            counter += counted_loop.step;
+            self.assign_variable(
+                counted_loop.counter_id,
+                counted_loop.counter,
+                Value::Number(counter as f64),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn to_display_string(&self, value: &Value) -> String {
+        match (value, self.precision) {
+            (Value::Number(n), Some(digits)) => {
+                // Round through scientific notation, then print the shortest representation
+                let rounded: f64 = format!("{:.*e}", digits.saturating_sub(1), n)
+                    .parse()
+                    .expect("failed to round number");
+                rounded.to_string()
+            }
+            _ => value.to_string(),
+        }
+    }
+
     pub fn execute_block(
         &mut self,
         statements: &[Stmt],
         environment: Rc<RefCell<Environment>>,
-    ) -> Result<(), RuntimeError> {
+    ) -> Result<(), Unwind> {
         // Save the previous environment
         let previous = self.environment.clone();
 
@@ -214,15 +686,25 @@ impl Interpreter {
         self.environment = environment;
 
         // Execute all statements inside the block
-        for stmt in statements {
-            self.execute(stmt)?;
-        }
+        let result = statements.iter().try_for_each(|stmt| self.execute(stmt));
 
-        // Restore the previous environment (outer scope)
+        // Restore the previous environment (outer scope), also when unwinding
         self.environment = previous;
 
-        Ok(())
+        result
+    }
+}
+
+/// A global scope holding only the native functions.
+fn new_globals() -> Rc<RefCell<Environment>> {
+    let globals = Environment::new();
+    for native in NATIVES {
+        globals
+            .borrow_mut()
+            .define(native.name().into(), Value::Callable(Rc::new(native)));
     }
+
+    globals
 }
 
 fn num_bin_op<F>(x: Value, y: Value, op: F) -> Result<Value, String>
@@ -236,15 +718,33 @@ where
     }
 }
 
+/// Compares two numbers, or two strings lexicographically. Comparisons with NaN are always false.
 fn bool_bin_op<F>(x: Value, y: Value, op: F) -> Result<Value, String>
 where
-    F: Fn(f64, f64) -> bool,
+    F: Fn(Ordering) -> bool,
 {
-    if let (Value::Number(x), Value::Number(y)) = (x, y) {
-        Ok(Value::Boolean(op(x, y)))
-    } else {
-        Err("Operands must be numbers/integers".into())
-    }
+    let ordering = match (&x, &y) {
+        (Value::Number(x), Value::Number(y)) => x.partial_cmp(y),
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        _ => {
+            return Err(format!(
+                "operands must be two numbers or two strings, got {} and {}",
+                x.type_name(),
+                y.type_name()
+            ));
+        }
+    };
+
+    Ok(Value::Boolean(ordering.is_some_and(op)))
+}
+
+/// Equality that never coerces: values of different types are never equal.
+fn strict_equals(x: &Value, y: &Value) -> bool {
+    std::mem::discriminant(x) == std::mem::discriminant(y) && x == y
+}
+
+fn is_finite(val: &Value) -> bool {
+    !matches!(val, Value::Number(n) if !n.is_finite())
 }
 
 fn is_truthy(val: &Value) -> bool {
@@ -260,6 +760,7 @@ fn error(message: String, token: &Token) -> RuntimeError {
         message,
         context: ErrorContext {
             line_number: token.line,
+            column: token.column,
             lexeme: token.lexeme.clone(),
         },
     }
@@ -276,9 +777,45 @@ mod tests {
             lexeme: "".into(),
             literal: None,
             line: 1,
+            column: 1,
         }
     }
 
+    #[test]
+    fn value_from_host_types() {
+        // Arrange
+        let values = [
+            Value::from(3.0),
+            Value::from("text"),
+            Value::from(String::from("owned")),
+            Value::from(true),
+        ];
+
+        // Act
+        let number = f64::try_from(values[0].clone());
+        let borrowed = String::try_from(values[1].clone());
+        let owned = String::try_from(values[2].clone());
+        let boolean = bool::try_from(values[3].clone());
+
+        // Assert
+        assert_eq!(number, Ok(3.0));
+        assert_eq!(borrowed, Ok("text".to_string()));
+        assert_eq!(owned, Ok("owned".to_string()));
+        assert_eq!(boolean, Ok(true));
+    }
+
+    #[test]
+    fn value_try_from_type_mismatch() {
+        // Arrange
+        let value = Value::from("3");
+
+        // Act
+        let result = f64::try_from(value);
+
+        // Assert
+        assert_eq!(result, Err("expected a number but got string".to_string()));
+    }
+
     fn new_binary_expression(left_value: f64, token_type: TokenType, right_value: f64) -> Expr {
         Expr::Binary {
             left: Box::new(Expr::Literal {
@@ -480,6 +1017,18 @@ mod tests {
         assert_eq!(result, Value::Number(2.0));
     }
 
+    #[test]
+    fn binary_modulo_numbers() {
+        // Arrange
+        let expr = new_binary_expression(7.0, TokenType::Percent, 3.0);
+
+        // Act
+        let result = Interpreter::new(vec![]).evaluate(&expr).unwrap();
+
+        // Assert
+        assert_eq!(result, Value::Number(1.0));
+    }
+
     #[test]
     fn binary_division_by_zero() {
         // Arrange
@@ -495,6 +1044,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn binary_overflow_is_infinite_by_default() {
+        // Arrange
+        let expr = new_binary_expression(1e308, TokenType::Star, 10.0);
+
+        // Act
+        let result = Interpreter::new(vec![]).evaluate(&expr).unwrap();
+
+        // Assert
+        assert_eq!(result, Value::Number(f64::INFINITY));
+    }
+
+    #[test]
+    fn binary_overflow_errors_with_safe_math() {
+        // Arrange
+        let expr = new_binary_expression(1e308, TokenType::Star, 10.0);
+
+        // Act
+        let result = Interpreter::new(vec![]).with_safe_math().evaluate(&expr);
+
+        // Assert
+        assert_eq!(result.unwrap_err().message, "numeric overflow");
+    }
+
+    #[test]
+    fn binary_infinite_operand_is_allowed_with_safe_math() {
+        // Arrange
+        let expr = new_binary_expression(f64::INFINITY, TokenType::Plus, 1.0);
+
+        // Act
+        let result = Interpreter::new(vec![]).with_safe_math().evaluate(&expr);
+
+        // Assert
+        assert_eq!(result.unwrap(), Value::Number(f64::INFINITY));
+    }
+
     #[test]
     fn binary_comparison_equal() {
         // Arrange
@@ -567,12 +1152,45 @@ mod tests {
         assert_eq!(result, Value::Boolean(true));
     }
 
+    #[test]
+    fn snapshot_and_restore_use_globals_inside_a_scope() {
+        // Arrange
+        let mut interpreter = Interpreter::new(vec![]);
+        interpreter
+            .globals
+            .borrow_mut()
+            .define("a".into(), Value::Number(1.0));
+        interpreter.environment = Environment::with_enclosing(interpreter.globals.clone());
+        interpreter
+            .environment
+            .borrow_mut()
+            .define("b".into(), Value::Number(2.0));
+
+        // Act
+        let snapshot = interpreter.snapshot();
+        interpreter
+            .globals
+            .borrow_mut()
+            .define("a".into(), Value::Number(3.0));
+        interpreter.restore(snapshot);
+
+        // Assert
+        assert_eq!(
+            interpreter.globals.borrow().get_local("a"),
+            Some(Value::Number(1.0))
+        );
+        assert_eq!(
+            interpreter.environment.borrow().get_local("b"),
+            Some(Value::Number(2.0))
+        );
+    }
+
     #[test]
     fn test_is_truthy() {
-        assert_eq!(is_truthy(&Value::Boolean(true)), true);
-        assert_eq!(is_truthy(&Value::Boolean(false)), false);
-        assert_eq!(is_truthy(&Value::Nil), false);
-        assert_eq!(is_truthy(&Value::String("hi".into())), true);
-        assert_eq!(is_truthy(&Value::Number(0.0)), true);
+        assert!(is_truthy(&Value::Boolean(true)));
+        assert!(!is_truthy(&Value::Boolean(false)));
+        assert!(!is_truthy(&Value::Nil));
+        assert!(is_truthy(&Value::String("hi".into())));
+        assert!(is_truthy(&Value::Number(0.0)));
     }
 }
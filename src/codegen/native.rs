@@ -0,0 +1,266 @@
+use crate::codegen::function::Callable;
+use crate::codegen::interpreter::{Interpreter, Value};
+use crate::codegen::runtime_error::RuntimeError;
+use crate::common::Token;
+use crate::common::error_context::ErrorContext;
+
+/// A function implemented in Rust that every program can call without declaring it.
+#[derive(Clone, Copy)]
+pub struct NativeFunction {
+    name: &'static str,
+    arity: usize,
+    /// Returns the error message on failure, it is reported at the call.
+    function: fn(&mut Interpreter, Vec<Value>) -> Result<Value, String>,
+}
+
+/// The longest string, in bytes, that natives will build.
+const MAX_STRING_LENGTH: usize = 1 << 30;
+
+/// Every native function, defined in the global scope before a program runs.
+pub const NATIVES: [NativeFunction; 10] = [
+    NativeFunction {
+        name: "gc",
+        arity: 0,
+        function: gc,
+    },
+    NativeFunction {
+        name: "gcd",
+        arity: 2,
+        function: gcd,
+    },
+    NativeFunction {
+        name: "lcm",
+        arity: 2,
+        function: lcm,
+    },
+    NativeFunction {
+        name: "len",
+        arity: 1,
+        function: len,
+    },
+    NativeFunction {
+        name: "pad_left",
+        arity: 3,
+        function: pad_left,
+    },
+    NativeFunction {
+        name: "pad_right",
+        arity: 3,
+        function: pad_right,
+    },
+    NativeFunction {
+        name: "print",
+        arity: 1,
+        function: print,
+    },
+    NativeFunction {
+        name: "repeat",
+        arity: 2,
+        function: repeat,
+    },
+    NativeFunction {
+        name: "round",
+        arity: 1,
+        function: round,
+    },
+    NativeFunction {
+        name: "round_half_even",
+        arity: 2,
+        function: round_half_even,
+    },
+];
+
+impl NativeFunction {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl Callable for NativeFunction {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        paren: &Token,
+    ) -> Result<Value, RuntimeError> {
+        (self.function)(interpreter, arguments).map_err(|message| RuntimeError {
+            message,
+            context: ErrorContext {
+                line_number: paren.line,
+                column: paren.column,
+                lexeme: paren.lexeme.clone(),
+            },
+        })
+    }
+}
+
+/// `print(value)`, the call form of the `print` statement, see `Parser::with_print_function`.
+fn print(interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    interpreter.print(&arguments[0]);
+    Ok(Value::Nil)
+}
+
+/// `gc()`, reclaiming environments kept alive only by reference cycles once the current
+/// top-level statement completes.
+fn gc(interpreter: &mut Interpreter, _: Vec<Value>) -> Result<Value, String> {
+    interpreter.request_gc();
+    Ok(Value::Nil)
+}
+
+/// `gcd(a, b)`, the greatest common divisor of two integers, never negative. `gcd(0, 0)` is 0.
+fn gcd(_: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    let [a, b] = integers(arguments)?;
+    Ok(Value::Number(euclid(a, b)))
+}
+
+/// `lcm(a, b)`, the least common multiple of two integers, never negative. It is 0 if either is 0.
+fn lcm(_: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    let [a, b] = integers(arguments)?;
+    if a == 0.0 || b == 0.0 {
+        return Ok(Value::Number(0.0));
+    }
+
+    let lcm = (a / euclid(a, b) * b).abs();
+    if lcm.is_infinite() {
+        return Err("lcm is too large to represent".into());
+    }
+
+    Ok(Value::Number(lcm))
+}
+
+/// The Euclidean algorithm, exact for integer-valued floats.
+fn euclid(a: f64, b: f64) -> f64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0.0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// `len(s)`, the number of characters in a string, counted in Unicode scalar values.
+fn len(_: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    let [s] = <[Value; 1]>::try_from(arguments).expect("the call checks the arity of natives");
+    let s = String::try_from(s)?;
+    Ok(Value::Number(s.chars().count() as f64))
+}
+
+/// `repeat(s, n)`, the string `s` repeated `n` times.
+fn repeat(_: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    let [s, n] = <[Value; 2]>::try_from(arguments).expect("the call checks the arity of natives");
+    let s = String::try_from(s)?;
+    let n = count(n, "count")?;
+    check_length(s.len().checked_mul(n))?;
+
+    Ok(Value::String(s.repeat(n)))
+}
+
+/// `pad_left(s, width, fill)`, `s` preceded by as many `fill` characters as needed to be `width` characters long.
+fn pad_left(_: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    let (s, padding) = padding(arguments)?;
+    Ok(Value::String(padding + &s))
+}
+
+/// `pad_right(s, width, fill)`, `s` followed by as many `fill` characters as needed to be `width` characters long.
+fn pad_right(_: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    let (s, padding) = padding(arguments)?;
+    Ok(Value::String(s + &padding))
+}
+
+/// Splits the arguments of the padding natives into the string and the padding it needs.
+/// Lengths are counted in Unicode scalar values, strings already `width` long are left as is.
+fn padding(arguments: Vec<Value>) -> Result<(String, String), String> {
+    let [s, width, fill] =
+        <[Value; 3]>::try_from(arguments).expect("the call checks the arity of natives");
+    let s = String::try_from(s)?;
+    let width = count(width, "width")?;
+    let fill = String::try_from(fill)?;
+
+    let mut chars = fill.chars();
+    let (Some(fill), None) = (chars.next(), chars.next()) else {
+        return Err("fill must be a single character".into());
+    };
+
+    let padding_width = width.saturating_sub(s.chars().count());
+    check_length(
+        fill.len_utf8()
+            .checked_mul(padding_width)
+            .and_then(|padding| padding.checked_add(s.len())),
+    )?;
+
+    let padding = fill.to_string().repeat(padding_width);
+    Ok((s, padding))
+}
+
+/// Rejects building a string of `length` bytes, where `None` means the length overflowed,
+/// so a huge count is a runtime error rather than an allocation failure aborting the process.
+fn check_length(length: Option<usize>) -> Result<(), String> {
+    match length {
+        Some(length) if length <= MAX_STRING_LENGTH => Ok(()),
+        _ => Err("resulting string is too large".into()),
+    }
+}
+
+/// Converts an argument to a count, which must be a non-negative integer.
+fn count(value: Value, name: &str) -> Result<usize, String> {
+    let n = f64::try_from(value)?;
+    if n < 0.0 || n.fract() != 0.0 || !n.is_finite() {
+        return Err(format!("{} must be a non-negative integer", name));
+    }
+
+    Ok(n as usize)
+}
+
+/// `round(n)`, rounding halfway cases away from zero.
+fn round(_: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    let [n] = numbers(arguments)?;
+    Ok(Value::Number(n.round()))
+}
+
+/// `round_half_even(n, digits)`, rounding to `digits` decimal places with halfway cases going to
+/// the even neighbour, so repeated roundings don't drift upwards.
+fn round_half_even(_: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, String> {
+    let [n, digits] = numbers(arguments)?;
+    if digits < 0.0 || digits.fract() != 0.0 {
+        return Err("digits must be a non-negative integer".into());
+    }
+
+    // From 2^52 up every number is whole, so there's nothing left to round. This also covers
+    // a scale that overflowed to infinity, which would turn the result into NaN
+    let scale = 10f64.powf(digits);
+    let scaled = n * scale;
+    if scaled.is_nan() || scaled.abs() >= 2f64.powi(52) {
+        return Ok(Value::Number(n));
+    }
+
+    Ok(Value::Number(scaled.round_ties_even() / scale))
+}
+
+/// Converts every argument to a number without a fractional part.
+fn integers<const N: usize>(arguments: Vec<Value>) -> Result<[f64; N], String> {
+    let integers = numbers(arguments)?;
+    match integers.iter().find(|n| n.fract() != 0.0 || !n.is_finite()) {
+        Some(n) => Err(format!("expected an integer but got {}", n)),
+        None => Ok(integers),
+    }
+}
+
+/// Converts every argument to a number, the arity having already been checked by the call.
+fn numbers<const N: usize>(arguments: Vec<Value>) -> Result<[f64; N], String> {
+    let numbers = arguments
+        .into_iter()
+        .map(f64::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(numbers
+        .try_into()
+        .expect("the call checks the arity of natives"))
+}
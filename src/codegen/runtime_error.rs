@@ -1,3 +1,4 @@
+use crate::codegen::interpreter::Value;
 use crate::common::error_context::{ErrorContext, PrettyError};
 use std::fmt;
 
@@ -22,3 +23,16 @@ impl fmt::Display for RuntimeError {
         self.pretty_fmt(f)
     }
 }
+
+/// Why executing statements stopped early: either an error, or a `return` unwinding to its call.
+#[derive(Debug)]
+pub enum Unwind {
+    Error(RuntimeError),
+    Return(Value),
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Unwind::Error(error)
+    }
+}
@@ -7,6 +7,8 @@ pub use token::Token;
 pub mod error_context;
 
 pub mod source_map;
+
 mod token_type;
+pub mod warning;
 
 pub use token_type::TokenType;
@@ -5,6 +5,8 @@ use std::fmt;
 #[derive(Clone, Debug)]
 pub struct ErrorContext {
     pub line_number: usize,
+    /// 1-based column of the lexeme's first character, counted in characters.
+    pub column: usize,
     pub lexeme: String,
 }
 
@@ -12,24 +14,41 @@ pub trait PrettyError: fmt::Display {
     fn message(&self) -> &str;
     fn context(&self) -> &ErrorContext;
 
+    fn is_warning(&self) -> bool {
+        false
+    }
+
     fn pretty_fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let ctx = self.context();
         let line = get_source_map()
-            .and_then(|m| m.get_line(ctx.line_number))
+            .and_then(|m| m.get_line(self.context().line_number))
             .unwrap_or("<source line unavailable>");
 
+        self.pretty_fmt_line(f, line)
+    }
+
+    /// Renders the error under the given source line, underlining the lexeme at its column.
+    fn pretty_fmt_line(&self, f: &mut fmt::Formatter, line: &str) -> fmt::Result {
+        let ctx = self.context();
         let first_lexeme_line = ctx.lexeme.lines().next().unwrap_or("");
 
-        let column_start = line.find(first_lexeme_line).unwrap_or(0);
-        let column_end = column_start + first_lexeme_line.len().max(1);
+        let column_start = ctx.column.saturating_sub(1);
+        let column_end = column_start + first_lexeme_line.chars().count().max(1);
 
         let line_prefix = format!("{:>4} | ", ctx.line_number).bright_blue().bold();
 
+        let highlight = |text: String| {
+            if self.is_warning() {
+                text.bright_yellow().bold()
+            } else {
+                text.bright_red().bold()
+            }
+        };
+
         let underline = format!(
             "{}{} {}",
             " ".repeat(line_prefix.len() + column_start),
-            "^".repeat(column_end - column_start).bright_red().bold(),
-            self.message().bright_red().bold()
+            highlight("^".repeat(column_end - column_start)),
+            highlight(self.message().to_string())
         );
 
         writeln!(f, "{}{}", line_prefix, line)?;
@@ -38,3 +57,38 @@ pub trait PrettyError: fmt::Display {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::interpreter::Interpreter;
+    use crate::parser::parser::Parser;
+    use crate::scanner::Scanner;
+
+    /// Renders an error against a given source line rather than the global source map.
+    struct Rendered<'a, E: PrettyError>(&'a E, &'a str);
+
+    impl<E: PrettyError> fmt::Display for Rendered<'_, E> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.pretty_fmt_line(f, self.1)
+        }
+    }
+
+    #[test]
+    fn underline_duplicated_lexeme_at_its_column() {
+        // Arrange
+        let source = "var b = b;";
+        let tokens = Scanner::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        let error = Interpreter::new(statements).interpret().unwrap_err();
+
+        // Act
+        let rendered = Rendered(&error, source).to_string();
+
+        // Assert
+        let underline = rendered.lines().nth(1).unwrap();
+        let indent = underline.chars().take_while(|&c| c == ' ').count();
+        assert_eq!(error.context().column, 9);
+        assert_eq!(indent, "   1 | ".len() + 8);
+    }
+}
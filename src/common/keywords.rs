@@ -5,9 +5,11 @@ use crate::common::TokenType;
 
 static KEYWORDS_MAP: OnceLock<HashMap<&'static str, TokenType>> = OnceLock::new();
 
-const KEYWORDS: [(&str, TokenType); 16] = [
+const KEYWORDS: [(&str, TokenType); 20] = [
     ("and", TokenType::And),
+    ("assert", TokenType::Assert),
     ("class", TokenType::Class),
+    ("elif", TokenType::Elif),
     ("else", TokenType::Else),
     ("false", TokenType::False),
     ("for", TokenType::For),
@@ -16,12 +18,14 @@ const KEYWORDS: [(&str, TokenType); 16] = [
     ("nil", TokenType::Nil),
     ("or", TokenType::Or),
     ("print", TokenType::Print),
+    ("printf", TokenType::Printf),
     ("return", TokenType::Return),
     ("super", TokenType::Super),
     ("this", TokenType::This),
     ("true", TokenType::True),
     ("var", TokenType::Var),
     ("while", TokenType::While),
+    ("xor", TokenType::Xor),
 ];
 
 pub fn keywords() -> &'static HashMap<&'static str, TokenType> {
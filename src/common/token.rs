@@ -4,6 +4,9 @@ use std::fmt;
 #[derive(Clone, Debug, PartialEq)]
 pub enum Literal {
     String(String),
+    /// A number literal written without a fractional part, e.g. `42`.
+    Integer(i64),
+    /// A number literal with a fractional part, e.g. `42.0`.
     Number(f64),
     Boolean(bool),
     Nil,
@@ -15,12 +18,15 @@ pub struct Token {
     pub lexeme: String,
     pub literal: Option<Literal>,
     pub line: usize,
+    /// 1-based position of the token's first character within its line, counted in characters.
+    pub column: usize,
 }
 
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let literal_str: String = match &self.literal {
             Some(Literal::String(s)) => s.clone(),
+            Some(Literal::Integer(n)) => n.to_string(),
             Some(Literal::Number(n)) => n.to_string(),
             Some(Literal::Boolean(b)) => format!("#{}", b),
             Some(Literal::Nil) => String::from("nil"),
@@ -34,3 +40,45 @@ impl fmt::Display for Token {
         )
     }
 }
+
+impl Token {
+    /// Serializes the token as a single-line JSON object, used for `--emit-tokens-json`.
+    pub fn to_json(&self) -> String {
+        let literal = match &self.literal {
+            Some(Literal::String(s)) => json_string(s),
+            Some(Literal::Integer(n)) => n.to_string(),
+            Some(Literal::Number(n)) => n.to_string(),
+            Some(Literal::Boolean(b)) => b.to_string(),
+            Some(Literal::Nil) | None => String::from("null"),
+        };
+
+        format!(
+            r#"{{"type":{},"lexeme":{},"line":{},"column":{},"literal":{}}}"#,
+            json_string(&format!("{:?}", self.token_type)),
+            json_string(&self.lexeme),
+            self.line,
+            self.column,
+            literal
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
@@ -12,12 +12,16 @@ pub enum TokenType {
     SemiColon,
     Slash,
     Star,
+    Percent,
+    At,
 
-    // One/Two character tokens (operators)
+    // One/Two/Three character tokens (operators)
     Bang,
     BangEqual,
+    BangEqualEqual,
     Equal,
     EqualEqual,
+    EqualEqualEqual,
     Greater,
     GreaterEqual,
     Less,
@@ -26,12 +30,16 @@ pub enum TokenType {
     // Literals
     Identifier,
     String,
+    /// A string segment followed by an interpolated `${...}` expression
+    Interpolation,
     Number,
 
     // Keywords
     And,
+    Assert,
     Class,
     If,
+    Elif,
     Else,
     True,
     False,
@@ -40,11 +48,16 @@ pub enum TokenType {
     Nil,
     Or,
     Print,
+    Printf,
     Return,
     Super,
     This,
     Var,
     While,
+    Xor,
+
+    // Keywords registered by an embedder, see `Scanner::with_keywords`
+    Custom(String),
 
     // End of File
     Eof,
@@ -0,0 +1,29 @@
+use crate::common::error_context::{ErrorContext, PrettyError};
+use std::fmt;
+
+/// A non-fatal diagnostic. Warnings are reported to the user but never stop a program from running.
+#[derive(Debug)]
+pub struct Warning {
+    pub message: String,
+    pub context: ErrorContext,
+}
+
+impl PrettyError for Warning {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn context(&self) -> &ErrorContext {
+        &self.context
+    }
+
+    fn is_warning(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.pretty_fmt(f)
+    }
+}
@@ -0,0 +1,8 @@
+pub mod ast;
+pub mod codegen;
+pub mod common;
+pub mod optimizer;
+pub mod parser;
+pub mod scanner;
+pub mod semantics;
+mod tests;
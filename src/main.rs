@@ -1,15 +1,11 @@
-mod ast;
-mod codegen;
-mod common;
-mod parser;
-mod scanner;
-mod semantics;
-mod tests;
-
-use crate::codegen::interpreter::Interpreter;
-use crate::common::source_map::set_source_map;
-use crate::parser::parser::Parser;
-use crate::scanner::Scanner;
+use rlox::ast::stats::count_nodes;
+use rlox::codegen::interpreter::Interpreter;
+use rlox::common::source_map::set_source_map;
+use rlox::optimizer::constant_folder::fold_constants;
+use rlox::parser::parser::Parser;
+use rlox::scanner::Scanner;
+use rlox::semantics::resolver::Resolver;
+use std::collections::HashMap;
 use std::env::Args;
 use std::io::Write;
 use std::path::Path;
@@ -17,7 +13,7 @@ use std::process::exit;
 use std::{env, fs, io};
 
 fn main() -> io::Result<()> {
-    if let Some(path_string) = get_path_argument() {
+    if let Some(path_string) = get_argument_value("--path") {
         let source = fs::read_to_string(Path::new(&path_string))?;
         run(&source);
     } else {
@@ -53,13 +49,13 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
-fn get_path_argument() -> Option<String> {
+fn get_argument_value(flag: &str) -> Option<String> {
     let mut args: Args = env::args();
     while let Some(arg) = args.next() {
-        if arg == "--path" {
+        if arg == flag {
             return Some(
                 args.next()
-                    .expect("No value provided for `--path` argument!"),
+                    .unwrap_or_else(|| panic!("No value provided for `{flag}` argument!")),
             );
         }
     }
@@ -67,14 +63,105 @@ fn get_path_argument() -> Option<String> {
     None
 }
 
+fn has_flag(flag: &str) -> bool {
+    env::args().any(|arg| arg == flag)
+}
+
 fn run(source: &String) {
     set_source_map(source.as_str());
 
-    let tokens = Scanner::new(source).tokenize().unwrap_or_else(|| exit(65));
+    let mut scanner = Scanner::new(source);
+    if has_flag("--forbid-tabs") {
+        scanner = scanner.forbid_tabs();
+    }
+    if has_flag("--warn-trailing-whitespace") {
+        scanner = scanner.warn_trailing_whitespace();
+    }
+
+    let tokens = scanner.tokenize().unwrap_or_else(|| exit(65));
+
+    if has_flag("--emit-tokens-json") {
+        for token in &tokens {
+            println!("{}", token.to_json());
+        }
+
+        return;
+    }
+
+    let mut parser = Parser::new(tokens);
+    if has_flag("--print-function") {
+        parser = parser.with_print_function();
+    }
+
+    let statements = parser.parse().unwrap_or_else(|| exit(65));
+
+    if has_flag("--ast-stats") {
+        print_node_counts(&count_nodes(&statements));
+        return;
+    }
+    let statements = fold_constants(statements);
+
+    let (locals, resolve_errors) = Resolver::new().resolve(&statements);
+    if !resolve_errors.is_empty() {
+        for error in resolve_errors {
+            eprintln!("{}", error);
+        }
+
+        exit(65);
+    }
+
+    let mut interpreter = Interpreter::new(statements).with_locals(locals);
+    if has_flag("--quiet") {
+        interpreter = interpreter.with_output(io::sink());
+    }
+    if has_flag("--safe-math") {
+        interpreter = interpreter.with_safe_math();
+    }
+    if let Some(precision) = get_argument_value("--precision") {
+        match precision.parse() {
+            Ok(digits) if digits > 0 => interpreter = interpreter.with_precision(digits),
+            _ => {
+                eprintln!("`--precision` must be a positive integer, got `{precision}`");
+                exit(64);
+            }
+        }
+    }
+    if has_flag("--profile-calls") {
+        interpreter = interpreter.with_call_profiling();
+    }
+
+    let result = interpreter.interpret();
+
+    if let Some(call_counts) = interpreter.call_counts() {
+        print_call_counts(call_counts);
+    }
 
-    let statements = Parser::new(tokens).parse().unwrap_or_else(|| exit(65));
+    if result.is_err() {
+        exit(70);
+    }
+}
 
-    Interpreter::new(statements)
-        .interpret()
-        .unwrap_or_else(|_| exit(70));
+/// Prints how often each kind of node appears in the program, most common first.
+fn print_node_counts(node_counts: &HashMap<&str, usize>) {
+    let mut node_counts: Vec<_> = node_counts.iter().collect();
+    node_counts.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+    });
+
+    for (name, count) in node_counts {
+        println!("{:>8} {}", count, name);
+    }
+}
+
+/// Prints the call report to stderr, most called functions first, so it stays apart from program output.
+fn print_call_counts(call_counts: &HashMap<String, usize>) {
+    let mut call_counts: Vec<_> = call_counts.iter().collect();
+    call_counts.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+    });
+
+    eprintln!("calls:");
+    for (name, count) in call_counts {
+        eprintln!("{:>8} {}", count, name);
+    }
 }
@@ -0,0 +1,303 @@
+use crate::ast::{Expr, InterpPart, Stmt};
+use crate::codegen::interpreter::{Interpreter, Value};
+use crate::common::Literal;
+
+/// Replaces sub-expressions built purely from literals with their value, e.g. `2 + 3 * 4` becomes `14`.
+/// Constant expressions are evaluated by the interpreter itself, so folding can never change a result.
+/// Expressions that fail to evaluate are left untouched so the error is still raised at runtime.
+pub fn fold_constants(statements: Vec<Stmt>) -> Vec<Stmt> {
+    let mut interpreter = Interpreter::new(vec![]);
+    fold_statements(statements, &mut interpreter)
+}
+
+fn fold_statements(statements: Vec<Stmt>, interpreter: &mut Interpreter) -> Vec<Stmt> {
+    statements
+        .into_iter()
+        .map(|stmt| fold_stmt(stmt, interpreter))
+        .collect()
+}
+
+fn fold_stmt(stmt: Stmt, interpreter: &mut Interpreter) -> Stmt {
+    match stmt {
+        Stmt::Assert {
+            keyword,
+            condition,
+            source,
+        } => Stmt::Assert {
+            keyword,
+            condition: Box::new(fold_expr(*condition, interpreter)),
+            source,
+        },
+        Stmt::Block { statements } => Stmt::Block {
+            statements: fold_statements(statements, interpreter),
+        },
+        Stmt::Class { name, methods } => Stmt::Class {
+            name,
+            methods: fold_statements(methods, interpreter),
+        },
+        Stmt::Expression { expression } => Stmt::Expression {
+            expression: Box::new(fold_expr(*expression, interpreter)),
+        },
+        Stmt::Function {
+            name,
+            parameters,
+            body,
+        } => Stmt::Function {
+            name,
+            parameters,
+            body: fold_statements(body.to_vec(), interpreter).into(),
+        },
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => Stmt::If {
+            condition: Box::new(fold_expr(*condition, interpreter)),
+            then_branch: Box::new(fold_stmt(*then_branch, interpreter)),
+            else_branch: else_branch.map(|stmt| Box::new(fold_stmt(*stmt, interpreter))),
+        },
+        Stmt::Print { expression } => Stmt::Print {
+            expression: Box::new(fold_expr(*expression, interpreter)),
+        },
+        Stmt::Printf {
+            keyword,
+            format,
+            arguments,
+        } => Stmt::Printf {
+            keyword,
+            format: Box::new(fold_expr(*format, interpreter)),
+            arguments: arguments
+                .into_iter()
+                .map(|expr| fold_expr(expr, interpreter))
+                .collect(),
+        },
+        Stmt::Return { keyword, value } => Stmt::Return {
+            keyword,
+            value: value.map(|expr| Box::new(fold_expr(*expr, interpreter))),
+        },
+        Stmt::Var { name, initializer } => Stmt::Var {
+            name,
+            initializer: initializer.map(|expr| Box::new(fold_expr(*expr, interpreter))),
+        },
+        Stmt::While { condition, body } => Stmt::While {
+            condition: Box::new(fold_expr(*condition, interpreter)),
+            body: Box::new(fold_stmt(*body, interpreter)),
+        },
+    }
+}
+
+fn fold_expr(expr: Expr, interpreter: &mut Interpreter) -> Expr {
+    let expr = match expr {
+        Expr::Assign { id, name, value } => Expr::Assign {
+            id,
+            name,
+            value: Box::new(fold_expr(*value, interpreter)),
+        },
+        Expr::Binary {
+            left,
+            operator,
+            right,
+        } => Expr::Binary {
+            left: Box::new(fold_expr(*left, interpreter)),
+            operator,
+            right: Box::new(fold_expr(*right, interpreter)),
+        },
+        Expr::Call {
+            callee,
+            paren,
+            arguments,
+        } => Expr::Call {
+            callee: Box::new(fold_expr(*callee, interpreter)),
+            paren,
+            arguments: arguments
+                .into_iter()
+                .map(|argument| fold_expr(argument, interpreter))
+                .collect(),
+        },
+        Expr::Get { object, name } => Expr::Get {
+            object: Box::new(fold_expr(*object, interpreter)),
+            name,
+        },
+        Expr::Grouping { expression } => Expr::Grouping {
+            expression: Box::new(fold_expr(*expression, interpreter)),
+        },
+        Expr::Interpolation { parts } => Expr::Interpolation {
+            parts: parts
+                .into_iter()
+                .map(|part| match part {
+                    InterpPart::Expr(expr) => {
+                        InterpPart::Expr(Box::new(fold_expr(*expr, interpreter)))
+                    }
+                    literal => literal,
+                })
+                .collect(),
+        },
+        Expr::Logical {
+            left,
+            operator,
+            right,
+        } => Expr::Logical {
+            left: Box::new(fold_expr(*left, interpreter)),
+            operator,
+            right: Box::new(fold_expr(*right, interpreter)),
+        },
+        Expr::Set {
+            object,
+            name,
+            value,
+        } => Expr::Set {
+            object: Box::new(fold_expr(*object, interpreter)),
+            name,
+            value: Box::new(fold_expr(*value, interpreter)),
+        },
+        Expr::Unary { operator, right } => Expr::Unary {
+            operator,
+            right: Box::new(fold_expr(*right, interpreter)),
+        },
+        Expr::Literal { .. } | Expr::This { .. } | Expr::Variable { .. } => return expr,
+    };
+
+    if !is_constant(&expr) {
+        return expr;
+    }
+
+    match interpreter.evaluate(&expr).ok().and_then(to_literal) {
+        Some(value) => Expr::Literal { value },
+        None => expr,
+    }
+}
+
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+            is_literal(left) && is_literal(right)
+        }
+        Expr::Grouping { expression } => is_literal(expression),
+        Expr::Unary { right, .. } => is_literal(right),
+        _ => false,
+    }
+}
+
+fn is_literal(expr: &Expr) -> bool {
+    matches!(expr, Expr::Literal { .. })
+}
+
+fn to_literal(value: Value) -> Option<Literal> {
+    match value {
+        // Overflow is left to the interpreter, which may be running in safe math mode
+        Value::Number(n) if !n.is_finite() => None,
+        Value::Number(n) => Some(Literal::Number(n)),
+        Value::String(s) => Some(Literal::String(s)),
+        Value::Boolean(b) => Some(Literal::Boolean(b)),
+        Value::Nil => Some(Literal::Nil),
+        Value::Callable(_) | Value::Class(_) | Value::Instance(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+    use crate::scanner::Scanner;
+
+    fn parse_expression(source: &str) -> Expr {
+        let tokens = Scanner::new(source).tokenize().unwrap();
+        match Parser::new(tokens).parse().unwrap().remove(0) {
+            Stmt::Expression { expression } => *expression,
+            _ => panic!("Expected expression statement."),
+        }
+    }
+
+    fn fold(source: &str) -> Expr {
+        fold_expr(parse_expression(source), &mut Interpreter::new(vec![]))
+    }
+
+    #[test]
+    fn fold_arithmetic() {
+        // Arrange
+        let source = "2 + 3 * 4;";
+
+        // Act
+        let folded = fold(source);
+
+        // Assert
+        assert_eq!(
+            folded,
+            Expr::Literal {
+                value: Literal::Number(14.0)
+            }
+        );
+    }
+
+    #[test]
+    fn fold_produces_same_result_as_evaluation() {
+        // Arrange
+        let source = "(1 + 2) * 3 == 9 and \"a\" + \"b\" == \"ab\";";
+        let expected = Interpreter::new(vec![])
+            .evaluate(&parse_expression(source))
+            .unwrap();
+
+        // Act
+        let folded = fold(source);
+
+        // Assert
+        let actual = Interpreter::new(vec![]).evaluate(&folded).unwrap();
+        assert!(is_literal(&folded));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn fold_negation() {
+        // Arrange
+        let source = "!true;";
+
+        // Act
+        let folded = fold(source);
+
+        // Assert
+        assert_eq!(
+            folded,
+            Expr::Literal {
+                value: Literal::Boolean(false)
+            }
+        );
+    }
+
+    #[test]
+    fn fold_keeps_runtime_errors() {
+        // Arrange
+        let source = "-\"oops\";";
+
+        // Act
+        let folded = fold(source);
+
+        // Assert
+        assert!(!is_literal(&folded));
+    }
+
+    #[test]
+    fn fold_does_not_fold_side_effects() {
+        // Arrange
+        let source = "(a = 1 + 2) + 3;";
+
+        // Act
+        let folded = fold(source);
+
+        // Assert
+        let Expr::Binary { left, .. } = folded else {
+            panic!("Expected binary expression.");
+        };
+        let Expr::Grouping { expression } = *left else {
+            panic!("Expected grouping expression.");
+        };
+        let Expr::Assign { value, .. } = *expression else {
+            panic!("Expected assignment expression.");
+        };
+        assert_eq!(
+            *value,
+            Expr::Literal {
+                value: Literal::Number(3.0)
+            }
+        );
+    }
+}
@@ -1,3 +1,5 @@
 mod parse_error;
 
+#[allow(clippy::module_inception)]
 pub mod parser;
+pub mod precedence;
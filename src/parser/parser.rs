@@ -1,42 +1,115 @@
-use crate::ast::{Expr, Stmt};
+use crate::ast::{Expr, InterpPart, Stmt, next_expr_id};
 use crate::common::error_context::ErrorContext;
+use crate::common::warning::Warning;
 use crate::common::{Literal, Token, TokenType};
 use crate::parser::parse_error::ParseError;
+use crate::parser::precedence::{Associativity, PrecedenceTable};
 
 #[derive(Debug)]
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    warnings: Vec<Warning>,
+    precedence: PrecedenceTable,
+    /// How many function bodies enclose the current token, `return` is only allowed inside one.
+    function_depth: usize,
+    /// Whether `print` is the name of the native function rather than a statement.
+    print_function: bool,
 }
 
 impl Parser {
+    const MAX_ARITY: usize = 255;
+
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, current: 0 }
+        Self {
+            tokens,
+            current: 0,
+            warnings: vec![],
+            precedence: PrecedenceTable::default(),
+            function_depth: 0,
+            print_function: false,
+        }
+    }
+
+    /// Parses `print` as a reference to the native `print(value)` function instead of the `print` statement.
+    pub fn with_print_function(mut self) -> Self {
+        self.print_function = true;
+        self
+    }
+
+    /// Parses binary operators with the given table instead of the standard Lox precedence.
+    pub fn with_precedence(mut self, precedence: PrecedenceTable) -> Self {
+        self.precedence = precedence;
+        self
+    }
+
+    pub fn precedence(&self) -> &PrecedenceTable {
+        &self.precedence
+    }
+
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
     }
 
     pub fn parse(&mut self) -> Option<Vec<Stmt>> {
+        let (statements, errors) = self.parse_with_recovery();
+
+        for warning in &self.warnings {
+            eprintln!("{}", warning);
+        }
+
+        if !errors.is_empty() {
+            for err in errors {
+                eprintln!("{}", err);
+            }
+
+            return None;
+        }
+
+        Some(statements)
+    }
+
+    /// Parses the whole program, returning every statement that could be parsed
+    /// alongside all errors encountered, instead of discarding the AST on error.
+    pub fn parse_with_recovery(&mut self) -> (Vec<Stmt>, Vec<ParseError>) {
+        let (brackets, mut errors): (Vec<usize>, Vec<ParseError>) =
+            check_brackets(&self.tokens).into_iter().unzip();
         let mut statements = Vec::new();
-        let mut has_error = false;
 
         while !self.is_at_end() {
+            let start = self.current;
             match self.declaration() {
                 Ok(stmt) => statements.push(stmt),
                 Err(err) => {
-                    has_error = true;
-                    eprintln!("{}", err);
                     self.synchronize();
+
+                    // An unbalanced bracket derails the declaration around it, which is already explained
+                    let span = start..self.current;
+                    if !brackets.iter().any(|bracket| span.contains(bracket)) {
+                        errors.push(err);
+                    }
                 }
             }
         }
 
-        if has_error {
-            return None;
-        }
-
-        Some(statements)
+        errors.sort_by_key(|err| (err.context.line_number, err.context.column));
+        (statements, errors)
     }
 
     fn declaration(&mut self) -> Result<Stmt, ParseError> {
+        // `@` is reserved for decorators
+        if self.check(&TokenType::At) {
+            return Err(self.error("decorators are not yet supported"));
+        }
+
+        if self.match_token(&[TokenType::Class]) {
+            return self.class_declaration();
+        }
+
+        if self.match_token(&[TokenType::Fun]) {
+            return self.function_declaration();
+        }
+
         if self.match_token(&[TokenType::Var]) {
             return self.variable_declaration();
         }
@@ -44,6 +117,61 @@ impl Parser {
         self.statement()
     }
 
+    fn class_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let name = self
+            .consume(&TokenType::Identifier, "expected class name")?
+            .clone();
+        self.consume(&TokenType::LeftBrace, "expected '{' before class body")?;
+
+        let mut methods = vec![];
+        while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+            methods.push(self.function_declaration()?);
+        }
+
+        self.consume(&TokenType::RightBrace, "expected '}' after class body")?;
+
+        Ok(Stmt::Class { name, methods })
+    }
+
+    fn function_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let name = self
+            .consume(&TokenType::Identifier, "expected function name")?
+            .clone();
+
+        self.consume(&TokenType::LeftParen, "expected '(' after function name")?;
+
+        let mut parameters = vec![];
+        if !self.check(&TokenType::RightParen) {
+            loop {
+                if parameters.len() >= Self::MAX_ARITY {
+                    return Err(self.error("can't have more than 255 parameters"));
+                }
+
+                parameters.push(
+                    self.consume(&TokenType::Identifier, "expected parameter name")?
+                        .clone(),
+                );
+
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume(&TokenType::RightParen, "expected ')' after parameters")?;
+        self.consume(&TokenType::LeftBrace, "expected '{' before function body")?;
+        self.function_depth += 1;
+        let body = self.block();
+        self.function_depth -= 1;
+        let body = body?;
+
+        Ok(Stmt::Function {
+            name,
+            parameters,
+            body: body.into(),
+        })
+    }
+
     fn variable_declaration(&mut self) -> Result<Stmt, ParseError> {
         let name = self
             .consume(&TokenType::Identifier, "expected variable name")?
@@ -67,6 +195,11 @@ impl Parser {
     }
 
     fn statement(&mut self) -> Result<Stmt, ParseError> {
+        // Assert Statement
+        if self.match_token(&[TokenType::Assert]) {
+            return self.assert_statement();
+        }
+
         // For Statement
         if self.match_token(&[TokenType::For]) {
             return self.for_statement();
@@ -78,10 +211,20 @@ impl Parser {
         }
 
         // Print Statement
-        if self.match_token(&[TokenType::Print]) {
+        if !self.print_function && self.match_token(&[TokenType::Print]) {
             return self.print_statement();
         }
 
+        // Printf Statement
+        if self.match_token(&[TokenType::Printf]) {
+            return self.printf_statement();
+        }
+
+        // Return Statement
+        if self.match_token(&[TokenType::Return]) {
+            return self.return_statement();
+        }
+
         // While Statement
         if self.match_token(&[TokenType::While]) {
             return self.while_statement();
@@ -95,6 +238,20 @@ impl Parser {
         self.expression_statement()
     }
 
+    fn assert_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous().clone();
+        let start = self.current;
+        let condition = self.expression()?;
+        let source = source_text(&self.tokens[start..self.current]);
+        self.consume(&TokenType::SemiColon, "expected ';' after assertion")?;
+
+        Ok(Stmt::Assert {
+            keyword,
+            condition: Box::new(condition),
+            source,
+        })
+    }
+
     fn for_statement(&mut self) -> Result<Stmt, ParseError> {
         self.consume(&TokenType::LeftParen, "expected '(' after 'for'")?;
 
@@ -124,7 +281,7 @@ impl Parser {
         self.consume(&TokenType::RightParen, "expected ')' after for clauses")?;
 
         // Parse body
-        let mut body = self.statement()?;
+        let mut body = self.body_statement()?;
 
         // Append increment after body, if present
         if let Some(inc) = increment {
@@ -158,17 +315,30 @@ impl Parser {
     }
 
     fn if_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous().clone();
         self.consume(&TokenType::LeftParen, "expected '(' after if")?;
         let condition = self.expression()?;
         self.consume(&TokenType::RightParen, "expected ')' after if")?;
 
-        let then_branch = self.statement()?;
+        let then_branch = self.body_statement()?;
         let else_branch = if self.match_token(&[TokenType::Else]) {
-            Some(Box::new(self.statement()?))
+            Some(Box::new(self.body_statement()?))
+        } else if self.match_token(&[TokenType::Elif]) {
+            // `elif` is sugar for `else if`
+            Some(Box::new(self.if_statement()?))
         } else {
             None
         };
 
+        if is_empty_block(&then_branch)
+            && else_branch.as_deref().is_some_and(|e| !is_empty_block(e))
+        {
+            self.warn(
+                "empty 'then' branch with a non-empty 'else'; consider negating the condition",
+                &keyword,
+            );
+        }
+
         Ok(Stmt::If {
             condition: Box::new(condition),
             then_branch: Box::new(then_branch),
@@ -185,11 +355,48 @@ impl Parser {
         })
     }
 
+    fn printf_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous().clone();
+        let format = self.expression()?;
+
+        let mut arguments = Vec::new();
+        while self.match_token(&[TokenType::Comma]) {
+            arguments.push(self.expression()?);
+        }
+        self.consume(&TokenType::SemiColon, "expected ';' after printf arguments")?;
+
+        Ok(Stmt::Printf {
+            keyword,
+            format: Box::new(format),
+            arguments,
+        })
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous().clone();
+        if self.function_depth == 0 {
+            return Err(error_at("can't return from top-level code", &keyword));
+        }
+
+        let value = if self.check(&TokenType::SemiColon) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.consume(&TokenType::SemiColon, "expected ';' after return value")?;
+
+        Ok(Stmt::Return {
+            keyword,
+            value: value.map(Box::new),
+        })
+    }
+
     fn while_statement(&mut self) -> Result<Stmt, ParseError> {
         self.consume(&TokenType::LeftParen, "expected '(' after 'while'")?;
         let condition = self.expression()?;
         self.consume(&TokenType::RightParen, "expected ')' after condition")?;
-        let body = self.statement()?;
+        let body = self.body_statement()?;
 
         Ok(Stmt::While {
             condition: Box::new(condition),
@@ -197,11 +404,42 @@ impl Parser {
         })
     }
 
+    /// Parses the body of a loop or `if`, where a lone `;` is accepted as an empty body.
+    /// That is usually a mistake, so it is reported as a warning.
+    fn body_statement(&mut self) -> Result<Stmt, ParseError> {
+        if self.match_token(&[TokenType::SemiColon]) {
+            let semicolon = self.previous().clone();
+            self.warn("empty body; use '{}' if this is intentional", &semicolon);
+
+            return Ok(Stmt::Block { statements: vec![] });
+        }
+
+        self.statement()
+    }
+
     fn block_statement(&mut self) -> Result<Stmt, ParseError> {
+        let statements = self.block()?;
+        Ok(Stmt::Block { statements })
+    }
+
+    /// Parses the statements of a block whose opening `{` was just consumed.
+    fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let opening_brace = self.previous().clone();
         let mut statements: Vec<Stmt> = vec![];
 
+        let unterminated = || error_at("unterminated block; expected '}'", &opening_brace);
+
         while !self.is_at_end() && !self.check(&TokenType::RightBrace) {
-            statements.push(self.declaration()?);
+            match self.declaration() {
+                Ok(stmt) => statements.push(stmt),
+                // Point at the opening brace, the EOF token has nothing to underline
+                Err(_) if self.is_at_end() => return Err(unterminated()),
+                Err(err) => return Err(err),
+            }
+        }
+
+        if self.is_at_end() {
+            return Err(unterminated());
         }
 
         self.consume(
@@ -209,7 +447,7 @@ impl Parser {
             "expected '}' after block statements",
         )?;
 
-        Ok(Stmt::Block { statements })
+        Ok(statements)
     }
 
     fn expression_statement(&mut self) -> Result<Stmt, ParseError> {
@@ -226,16 +464,38 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> Result<Expr, ParseError> {
-        let expr = self.or()?;
+        let expr = self.binary(0)?;
 
         if self.match_token(&[TokenType::Equal]) {
             let value = self.assignment()?;
 
-            if let Expr::Variable { name } = expr {
-                return Ok(Expr::Assign {
-                    name,
-                    value: Box::new(value),
-                });
+            match expr {
+                Expr::Variable { name, .. } => {
+                    let is_self_assignment = matches!(
+                        &value,
+                        Expr::Variable { name: source, .. } if source.lexeme == name.lexeme
+                    );
+                    if is_self_assignment {
+                        self.warn(
+                            "assigning a variable to itself has no effect; is this a typo?",
+                            &name,
+                        );
+                    }
+
+                    return Ok(Expr::Assign {
+                        id: next_expr_id(),
+                        name,
+                        value: Box::new(value),
+                    });
+                }
+                Expr::Get { object, name } => {
+                    return Ok(Expr::Set {
+                        object,
+                        name,
+                        value: Box::new(value),
+                    });
+                }
+                _ => {}
             }
 
             return Err(self.error("invalid variable assignment"));
@@ -244,118 +504,101 @@ impl Parser {
         Ok(expr)
     }
 
-    fn or(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.and()?;
+    /// Parses binary and logical operators by precedence climbing over the precedence table,
+    /// only consuming operators that bind at least as tightly as `min_precedence`.
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
 
-        while self.match_token(&[TokenType::Or]) {
-            let operator = self.previous().clone();
-            let right = self.and()?;
-            expr = Expr::Logical {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
+        while let Some(operator) = self
+            .precedence
+            .get(&self.peek().token_type)
+            .filter(|op| op.precedence >= min_precedence)
+            .cloned()
+        {
+            let token = self.advance().clone();
+            let right = match operator.associativity {
+                Associativity::Left => self.binary(operator.precedence.saturating_add(1))?,
+                Associativity::Right => self.binary(operator.precedence)?,
             };
-        }
 
-        Ok(expr)
-    }
-
-    fn and(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.equality()?;
-
-        while self.match_token(&[TokenType::And]) {
-            let operator = self.previous().clone();
-            let right = self.equality()?;
-            expr = Expr::Logical {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
+            expr = match token.token_type {
+                TokenType::And | TokenType::Or | TokenType::Xor => Expr::Logical {
+                    left: Box::new(expr),
+                    operator: token,
+                    right: Box::new(right),
+                },
+                _ => Expr::Binary {
+                    left: Box::new(expr),
+                    operator: token,
+                    right: Box::new(right),
+                },
             };
         }
 
         Ok(expr)
     }
 
-    fn equality(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.comparison()?;
-
-        while self.match_token(&[TokenType::BangEqual, TokenType::EqualEqual]) {
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.match_token(&[TokenType::Bang, TokenType::Minus]) {
             let operator = self.previous().clone();
-            let right = self.comparison()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
+            let right = self.unary()?;
+            Ok(Expr::Unary {
                 operator,
                 right: Box::new(right),
-            };
+            })
+        } else {
+            self.call()
         }
-
-        Ok(expr)
     }
 
-    fn comparison(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.term()?;
-
-        while self.match_token(&[
-            TokenType::Greater,
-            TokenType::GreaterEqual,
-            TokenType::Less,
-            TokenType::LessEqual,
-        ]) {
-            let operator = self.previous().clone();
-            let right = self.term()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            };
+    fn call(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.match_token(&[TokenType::LeftParen]) {
+                expr = self.finish_call(expr)?;
+            } else if self.match_token(&[TokenType::Dot]) {
+                let name = self
+                    .consume(&TokenType::Identifier, "expected property name after '.'")?
+                    .clone();
+                expr = Expr::Get {
+                    object: Box::new(expr),
+                    name,
+                };
+            } else {
+                break;
+            }
         }
 
         Ok(expr)
     }
 
-    fn term(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.factor()?;
-
-        while self.match_token(&[TokenType::Minus, TokenType::Plus]) {
-            let operator = self.previous().clone();
-            let right = self.factor()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            };
-        }
-
-        Ok(expr)
-    }
+    /// Parses the arguments of a call whose opening `(` was just consumed.
+    fn finish_call(&mut self, callee: Expr) -> Result<Expr, ParseError> {
+        let mut arguments = vec![];
+        if !self.check(&TokenType::RightParen) {
+            loop {
+                if arguments.len() >= Self::MAX_ARITY {
+                    return Err(self.error("can't have more than 255 arguments"));
+                }
 
-    fn factor(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.unary()?;
+                arguments.push(self.expression()?);
 
-        while self.match_token(&[TokenType::Slash, TokenType::Star]) {
-            let operator = self.previous().clone();
-            let right = self.unary()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            };
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
         }
 
-        Ok(expr)
-    }
+        let paren = self
+            .consume(&TokenType::RightParen, "expected ')' after arguments")?
+            .clone();
 
-    fn unary(&mut self) -> Result<Expr, ParseError> {
-        if self.match_token(&[TokenType::Bang, TokenType::Minus]) {
-            let operator = self.previous().clone();
-            let right = self.unary()?;
-            Ok(Expr::Unary {
-                operator,
-                right: Box::new(right),
-            })
-        } else {
-            self.primary()
-        }
+        Ok(Expr::Call {
+            callee: Box::new(callee),
+            paren,
+            arguments,
+        })
     }
 
     fn primary(&mut self) -> Result<Expr, ParseError> {
@@ -378,17 +621,45 @@ impl Parser {
         }
 
         if self.match_token(&[TokenType::Number, TokenType::String]) {
-            return Ok(Expr::Literal {
-                value: self
-                    .previous()
-                    .literal
-                    .clone()
-                    .expect("expected literal value"),
+            let literal = self
+                .previous()
+                .literal
+                .clone()
+                .expect("expected literal value");
+
+            // Integers and floats are collapsed into a single number type for now
+            let value = match literal {
+                Literal::Integer(n) => Literal::Number(n as f64),
+                literal => literal,
+            };
+
+            return Ok(Expr::Literal { value });
+        }
+
+        if self.match_token(&[TokenType::Interpolation]) {
+            return self.interpolation();
+        }
+
+        if self.match_token(&[TokenType::This]) {
+            return Ok(Expr::This {
+                id: next_expr_id(),
+                keyword: self.previous().clone(),
+            });
+        }
+
+        if self.print_function && self.match_token(&[TokenType::Print]) {
+            return Ok(Expr::Variable {
+                id: next_expr_id(),
+                name: Token {
+                    token_type: TokenType::Identifier,
+                    ..self.previous().clone()
+                },
             });
         }
 
         if self.match_token(&[TokenType::Identifier]) {
             return Ok(Expr::Variable {
+                id: next_expr_id(),
                 name: self.previous().clone(),
             });
         }
@@ -404,6 +675,31 @@ impl Parser {
         Err(self.error("expected expression"))
     }
 
+    /// Parses the segments of an interpolated string, the first `Interpolation` token being already consumed.
+    fn interpolation(&mut self) -> Result<Expr, ParseError> {
+        let mut parts = vec![];
+
+        loop {
+            if let Some(Literal::String(text)) = self.previous().literal.clone() {
+                if !text.is_empty() {
+                    parts.push(InterpPart::Literal(text));
+                }
+            }
+
+            if self.previous().token_type == TokenType::String {
+                break;
+            }
+
+            parts.push(InterpPart::Expr(Box::new(self.expression()?)));
+
+            if !self.match_token(&[TokenType::Interpolation, TokenType::String]) {
+                return Err(self.error("expected '}' after interpolated expression"));
+            }
+        }
+
+        Ok(Expr::Interpolation { parts })
+    }
+
     fn match_token(&mut self, types: &[TokenType]) -> bool {
         for t in types {
             if self.check(t) {
@@ -441,21 +737,35 @@ impl Parser {
         if self.check(token_type) {
             Ok(self.advance())
         } else {
-            Err(self.error(message))
+            Err(self.error_found(message))
         }
     }
 
     fn error(&self, message: &str) -> ParseError {
+        error_at(message, self.peek())
+    }
+
+    /// Builds an error at the current token, naming the token that was found instead of the expected one.
+    fn error_found(&self, message: &str) -> ParseError {
         let token = self.peek();
-        let line_number = token.line;
+        let message = if token.token_type == TokenType::Eof {
+            format!("{message}, but found end of file")
+        } else {
+            format!("{message}, but found '{}'", token.lexeme)
+        };
+
+        error_at(&message, token)
+    }
 
-        ParseError {
+    fn warn(&mut self, message: &str, token: &Token) {
+        self.warnings.push(Warning {
             message: message.into(),
             context: ErrorContext {
-                line_number,
+                line_number: token.line,
+                column: token.column,
                 lexeme: token.lexeme.clone(),
             },
-        }
+        });
     }
 
     fn synchronize(&mut self) {
@@ -474,7 +784,9 @@ impl Parser {
                 TokenType::If => {}
                 TokenType::While => {}
                 TokenType::Print => {}
+                TokenType::Printf => {}
                 TokenType::Return => {}
+                TokenType::Assert => {}
                 _ => {}
             }
 
@@ -483,6 +795,84 @@ impl Parser {
     }
 }
 
+/// Reassembles the source text the tokens were scanned from. Tokens that were adjacent stay
+/// adjacent, any other whitespace between them, including line breaks, becomes a single space.
+fn source_text(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    let mut previous: Option<&Token> = None;
+
+    for token in tokens {
+        let adjacent = previous.is_none_or(|previous| {
+            previous.line == token.line
+                && previous.column + previous.lexeme.chars().count() == token.column
+        });
+        if !adjacent {
+            text.push(' ');
+        }
+
+        text.push_str(&token.lexeme);
+        previous = Some(token);
+    }
+
+    text
+}
+
+fn error_at(message: &str, token: &Token) -> ParseError {
+    ParseError {
+        message: message.into(),
+        context: ErrorContext {
+            line_number: token.line,
+            column: token.column,
+            lexeme: token.lexeme.clone(),
+        },
+    }
+}
+
+/// Matches up brackets with a stack, reporting closers without an opener and `(`s left unclosed,
+/// each alongside the index of the offending token. Unclosed blocks are reported by `block` instead.
+fn check_brackets(tokens: &[Token]) -> Vec<(usize, ParseError)> {
+    let mut open: Vec<(usize, &Token)> = vec![];
+    let mut errors = vec![];
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token.token_type {
+            TokenType::LeftParen | TokenType::LeftBrace => open.push((index, token)),
+            TokenType::RightParen | TokenType::RightBrace => {
+                let (opener, opener_lexeme) = match token.token_type {
+                    TokenType::RightParen => (TokenType::LeftParen, "("),
+                    _ => (TokenType::LeftBrace, "{"),
+                };
+
+                if open
+                    .last()
+                    .is_some_and(|(_, last)| last.token_type == opener)
+                {
+                    open.pop();
+                } else {
+                    let message = format!(
+                        "unexpected '{}' with no matching '{}'",
+                        token.lexeme, opener_lexeme
+                    );
+                    errors.push((index, error_at(&message, token)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (index, opener) in open {
+        if opener.token_type == TokenType::LeftParen {
+            errors.push((index, error_at("unclosed '('; expected ')'", opener)));
+        }
+    }
+
+    errors
+}
+
+fn is_empty_block(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::Block { statements } if statements.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -495,6 +885,7 @@ mod tests {
             lexeme: lexeme.to_string(),
             literal,
             line: 1,
+            column: 1,
         }
     }
 
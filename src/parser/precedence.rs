@@ -0,0 +1,131 @@
+use crate::common::TokenType;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BinaryOperator {
+    pub token_type: TokenType,
+    /// Higher binds tighter. Prefix operators always bind tighter than any binary operator.
+    pub precedence: u8,
+    pub associativity: Associativity,
+}
+
+/// The binding power of each binary operator, used by the parser to climb precedence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrecedenceTable {
+    operators: Vec<BinaryOperator>,
+}
+
+impl Default for PrecedenceTable {
+    /// The standard Lox grammar: `or`/`xor`, `and`, equality, comparison, term, then factor.
+    fn default() -> Self {
+        let levels = [
+            vec![TokenType::Or, TokenType::Xor],
+            vec![TokenType::And],
+            vec![
+                TokenType::BangEqual,
+                TokenType::EqualEqual,
+                TokenType::BangEqualEqual,
+                TokenType::EqualEqualEqual,
+            ],
+            vec![
+                TokenType::Greater,
+                TokenType::GreaterEqual,
+                TokenType::Less,
+                TokenType::LessEqual,
+            ],
+            vec![TokenType::Minus, TokenType::Plus],
+            vec![TokenType::Slash, TokenType::Star, TokenType::Percent],
+        ];
+
+        let mut table = Self { operators: vec![] };
+        for (precedence, token_types) in (1..).zip(levels) {
+            for token_type in token_types {
+                table = table.with_operator(token_type, precedence, Associativity::Left);
+            }
+        }
+
+        table
+    }
+}
+
+impl PrecedenceTable {
+    /// Adds an operator, or overrides its precedence and associativity if it is already in the table.
+    pub fn with_operator(
+        mut self,
+        token_type: TokenType,
+        precedence: u8,
+        associativity: Associativity,
+    ) -> Self {
+        let operator = BinaryOperator {
+            token_type,
+            precedence,
+            associativity,
+        };
+
+        match self
+            .operators
+            .iter_mut()
+            .find(|op| op.token_type == operator.token_type)
+        {
+            Some(existing) => *existing = operator,
+            None => self.operators.push(operator),
+        }
+
+        self
+    }
+
+    pub fn get(&self, token_type: &TokenType) -> Option<&BinaryOperator> {
+        self.operators
+            .iter()
+            .find(|op| &op.token_type == token_type)
+    }
+
+    pub fn operators(&self) -> &[BinaryOperator] {
+        &self.operators
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_table_orders_factor_above_term() {
+        // Arrange
+        let table = PrecedenceTable::default();
+
+        // Act
+        let star = table.get(&TokenType::Star).unwrap();
+        let plus = table.get(&TokenType::Plus).unwrap();
+
+        // Assert
+        assert!(star.precedence > plus.precedence);
+        assert_eq!(plus.associativity, Associativity::Left);
+    }
+
+    #[test]
+    fn with_operator_overrides_existing_entry() {
+        // Arrange
+        let table = PrecedenceTable::default();
+        let count = table.operators().len();
+
+        // Act
+        let table = table.with_operator(TokenType::Minus, 9, Associativity::Right);
+
+        // Assert
+        assert_eq!(table.operators().len(), count);
+        assert_eq!(
+            table.get(&TokenType::Minus),
+            Some(&BinaryOperator {
+                token_type: TokenType::Minus,
+                precedence: 9,
+                associativity: Associativity::Right,
+            })
+        );
+    }
+}
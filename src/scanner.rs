@@ -1,4 +1,5 @@
 mod scan_error;
 
+#[allow(clippy::module_inception)]
 pub mod scanner;
 pub use scanner::Scanner;
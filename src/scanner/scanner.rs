@@ -1,47 +1,133 @@
 use crate::common::error_context::ErrorContext;
 use crate::common::keywords::keywords;
+use crate::common::warning::Warning;
 use crate::common::{Literal, Token, TokenType};
 use crate::scanner::scan_error::ScanError;
+use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct Scanner {
-    source: String,
+    source: Vec<char>,
     tokens: Vec<Token>,
+    warnings: Vec<Warning>,
+    /// Open brace depth of each `${...}` currently being scanned, innermost last.
+    interpolations: Vec<usize>,
     start: usize,
     current: usize,
     line: usize,
+    /// Index of the first character of the current line.
+    line_start: usize,
+    /// Index of the first character of the line the current token starts on.
+    token_line_start: usize,
+    at_line_start: bool,
+    forbid_tabs: bool,
+    warn_trailing_whitespace: bool,
+    extra_keywords: HashMap<String, TokenType>,
 }
 
 impl Scanner {
+    const MAX_SAFE_INTEGER: i64 = 1 << 53;
+
     pub fn new(source: impl Into<String>) -> Self {
         Self {
-            source: source.into(),
+            source: source.into().chars().collect(),
             tokens: vec![],
+            warnings: vec![],
+            interpolations: vec![],
             start: 0,
             current: 0,
             line: 1,
+            line_start: 0,
+            token_line_start: 0,
+            at_line_start: true,
+            forbid_tabs: false,
+            warn_trailing_whitespace: false,
+            extra_keywords: HashMap::new(),
         }
     }
 
+    /// Registers additional keywords on top of the built-in ones, which take precedence.
+    pub fn with_keywords(mut self, extra: HashMap<String, TokenType>) -> Self {
+        self.extra_keywords.extend(extra);
+        self
+    }
+
+    /// Rejects tabs used for indentation, for style-strict environments.
+    pub fn forbid_tabs(mut self) -> Self {
+        self.forbid_tabs = true;
+        self
+    }
+
+    /// Warns about every line ending in spaces or tabs, the tokens are the same either way.
+    pub fn warn_trailing_whitespace(mut self) -> Self {
+        self.warn_trailing_whitespace = true;
+        self
+    }
+
     pub fn tokenize(mut self) -> Option<Vec<Token>> {
+        let has_error = self.scan_tokens();
+
+        for warning in &self.warnings {
+            eprintln!("{}", warning);
+        }
+
+        if has_error { None } else { Some(self.tokens) }
+    }
+
+    fn scan_tokens(&mut self) -> bool {
         let mut has_error = false;
 
+        if self.warn_trailing_whitespace {
+            self.check_trailing_whitespace();
+        }
+
         while !self.is_at_end() {
             self.start = self.current;
+            self.token_line_start = self.line_start;
             if let Err(e) = self.scan_token() {
                 has_error = true;
                 eprintln!("{}", e);
             }
         }
 
+        if !self.interpolations.is_empty() {
+            has_error = true;
+            eprintln!(
+                "{}",
+                self.error_at_current("unterminated string interpolation")
+            );
+        }
+
         self.tokens.push(Token {
             token_type: TokenType::Eof,
             lexeme: String::new(),
             literal: None,
             line: self.line,
+            column: self.current - self.line_start + 1,
         });
 
-        if has_error { None } else { Some(self.tokens) }
+        has_error
+    }
+
+    fn check_trailing_whitespace(&mut self) {
+        let source: String = self.source.iter().collect();
+
+        for (index, line) in source.split('\n').enumerate() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let content = line.trim_end_matches([' ', '\t']);
+            if content.len() == line.len() {
+                continue;
+            }
+
+            self.warnings.push(Warning {
+                message: "trailing whitespace".into(),
+                context: ErrorContext {
+                    line_number: index + 1,
+                    column: content.chars().count() + 1,
+                    lexeme: line[content.len()..].into(),
+                },
+            });
+        }
     }
 
     fn is_at_end(&self) -> bool {
@@ -51,27 +137,57 @@ impl Scanner {
     fn scan_token(&mut self) -> Result<(), ScanError> {
         let c = self.advance();
 
+        if !matches!(c, ' ' | '\r' | '\t' | '\n') {
+            self.at_line_start = false;
+        }
+
         match c {
             '(' => self.add_token(TokenType::LeftParen),
             ')' => self.add_token(TokenType::RightParen),
-            '{' => self.add_token(TokenType::LeftBrace),
-            '}' => self.add_token(TokenType::RightBrace),
+            '{' => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+                self.add_token(TokenType::LeftBrace)
+            }
+            '}' => match self.interpolations.last_mut() {
+                // Closes an interpolated expression, the string continues after it
+                Some(0) => {
+                    self.interpolations.pop();
+                    self.string()?
+                }
+                Some(depth) => {
+                    *depth -= 1;
+                    self.add_token(TokenType::RightBrace)
+                }
+                None => self.add_token(TokenType::RightBrace),
+            },
             ',' => self.add_token(TokenType::Comma),
             '.' => self.add_token(TokenType::Dot),
             '-' => self.add_token(TokenType::Minus),
             '+' => self.add_token(TokenType::Plus),
             ';' => self.add_token(TokenType::SemiColon),
             '*' => self.add_token(TokenType::Star),
+            '%' => self.add_token(TokenType::Percent),
+            '@' => self.add_token(TokenType::At),
             '!' => {
                 if self.match_next_char('=') {
-                    self.add_token(TokenType::BangEqual)
+                    if self.match_next_char('=') {
+                        self.add_token(TokenType::BangEqualEqual)
+                    } else {
+                        self.add_token(TokenType::BangEqual)
+                    }
                 } else {
                     self.add_token(TokenType::Bang)
                 }
             }
             '=' => {
                 if self.match_next_char('=') {
-                    self.add_token(TokenType::EqualEqual)
+                    if self.match_next_char('=') {
+                        self.add_token(TokenType::EqualEqualEqual)
+                    } else {
+                        self.add_token(TokenType::EqualEqual)
+                    }
                 } else {
                     self.add_token(TokenType::Equal)
                 }
@@ -98,30 +214,44 @@ impl Scanner {
                 } else if self.match_next_char('*') {
                     let comment_start_line = self.line;
 
-                    // Start of multi-line comment
-                    while !(self.is_at_end() || self.peek() == '*' && self.peek_next() == '/') {
-                        if self.peek() == '\n' {
-                            self.line += 1;
+                    // Start of multi-line comment, which may contain nested comments
+                    let mut depth = 1;
+                    while depth > 0 {
+                        if self.is_at_end() {
+                            return Err(self.error_at_line(
+                                "unterminated multi-line comment",
+                                comment_start_line,
+                            ));
                         }
-                        self.advance();
-                    }
 
-                    // Consume the '*/' if found
-                    if !self.is_at_end() {
-                        self.advance(); // consume '*'
-                        self.advance(); // consume '/'
-                    } else {
-                        return Err(self
-                            .error_at_line("unterminated multi-line comment", comment_start_line));
+                        match self.advance() {
+                            '/' if self.match_next_char('*') => depth += 1,
+                            '*' if self.match_next_char('/') => depth -= 1,
+                            '\n' => self.newline(),
+                            _ => {}
+                        }
                     }
                 } else {
                     self.add_token(TokenType::Slash);
                 }
             }
+            '\t' if self.forbid_tabs && self.at_line_start => {
+                return Err(self.error_at_current("tabs are not allowed; use spaces"));
+            }
             ' ' | '\r' | '\t' => {} // Ignore whitespace
-            '\n' => self.line += 1,
+            '\n' => {
+                self.newline();
+                self.at_line_start = true;
+            }
+            // A backslash at the end of a line joins it with the next one
+            '\\' if self.peek() == '\n' || self.peek() == '\r' && self.peek_next() == '\n' => {
+                self.match_next_char('\r');
+                self.advance();
+                self.newline();
+            }
             '"' => self.string()?,
-            c if c.is_ascii_digit() => self.number(),
+            'r' if self.peek() == '"' => self.raw_string()?,
+            c if c.is_ascii_digit() => self.number()?,
             c if c.is_ascii_alphanumeric() || c == '_' => self.identifier(),
             _ => return Err(self.error_at_current("unexpected character")),
         }
@@ -129,11 +259,16 @@ impl Scanner {
         Ok(())
     }
 
+    /// Moves on to the next line, right after its `\n` has been consumed.
+    fn newline(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
     fn advance(&mut self) -> char {
-        let c = self
+        let c = *self
             .source
-            .chars()
-            .nth(self.current)
+            .get(self.current)
             .expect("Cannot advance past source");
 
         self.current += 1;
@@ -145,12 +280,13 @@ impl Scanner {
     }
 
     fn add_token_literal(&mut self, token_type: TokenType, literal: Option<Literal>) {
-        let text = self.source[self.start..self.current].to_string();
+        let text = self.substring(self.start, self.current);
         self.tokens.push(Token {
             token_type,
             lexeme: text,
             literal,
             line: self.line,
+            column: self.start_column(),
         });
     }
 
@@ -159,7 +295,7 @@ impl Scanner {
             return false;
         }
 
-        if self.source.chars().nth(self.current).unwrap() != expected {
+        if self.source[self.current] != expected {
             return false;
         }
 
@@ -172,7 +308,7 @@ impl Scanner {
             return '\0';
         }
 
-        self.source.chars().nth(self.current).unwrap()
+        self.source[self.current]
     }
 
     fn peek_next(&self) -> char {
@@ -180,88 +316,206 @@ impl Scanner {
             return '\0';
         }
 
-        self.source.chars().nth(self.current + 1).unwrap()
+        self.source[self.current + 1]
     }
 
+    /// Scans a string literal up to its closing `"`, or up to a `${` that starts an interpolated expression.
+    /// In the latter case the string is resumed by the `}` that closes the expression.
+    /// Escape sequences are decoded into the literal's value, while the lexeme keeps the raw source text.
     fn string(&mut self) -> Result<(), ScanError> {
         let start_line = self.line;
+        let mut value = String::new();
+        // An unknown escape is reported once the string ends, so its remainder isn't scanned as code
+        let mut escape_error = None;
+
+        loop {
+            if self.is_at_end() {
+                return Err(self.error_at_line("Unterminated string", start_line));
+            }
+
+            match self.advance() {
+                '"' => break,
+                '$' if self.peek() == '{' => {
+                    self.advance();
+                    self.interpolations.push(0);
+                    self.add_token_literal(TokenType::Interpolation, Some(Literal::String(value)));
+
+                    return escape_error.map_or(Ok(()), Err);
+                }
+                '\\' if !self.is_at_end() => match self.advance() {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    'r' => value.push('\r'),
+                    '0' => value.push('\0'),
+                    c @ ('"' | '\\' | '$') => value.push(c),
+                    c => {
+                        escape_error.get_or_insert(self.unknown_escape(c));
+                        if c == '\n' {
+                            self.newline();
+                        }
+                    }
+                },
+                '\n' => {
+                    self.newline();
+                    value.push('\n');
+                }
+                c => value.push(c),
+            }
+        }
+
+        if let Some(error) = escape_error {
+            return Err(error);
+        }
+
+        self.add_token_literal(TokenType::String, Some(Literal::String(value)));
+
+        Ok(())
+    }
+
+    fn unknown_escape(&self, escaped: char) -> ScanError {
+        ScanError {
+            message: format!("unknown escape sequence '\\{}'", escaped.escape_default()),
+            context: ErrorContext {
+                line_number: self.line,
+                // The escape is the two characters just consumed, still on the current line
+                column: self.current - 2 - self.line_start + 1,
+                lexeme: format!("\\{}", escaped),
+            },
+        }
+    }
+
+    /// Scans `r"..."`, a string whose content is taken verbatim without escape processing.
+    fn raw_string(&mut self) -> Result<(), ScanError> {
+        let start_line = self.line;
+
+        // Consume the opening "
+        self.advance();
 
-        // Trying to find the end of the string
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1;
+            if self.advance() == '\n' {
+                self.newline();
             }
-            self.advance();
         }
 
         if self.is_at_end() {
-            return Err(self.error_at_line("Unterminated string", start_line));
+            return Err(self.error_at_line("Unterminated raw string", start_line));
         }
 
         // Get the closing "
         self.advance();
 
-        // Trim the surrounding quotes of the value
-        let value = self.source[self.start + 1..self.current - 1].to_string();
+        // Trim the leading r and the surrounding quotes of the value
+        let value = self.substring(self.start + 2, self.current - 1);
         self.add_token_literal(TokenType::String, Some(Literal::String(value)));
 
         Ok(())
     }
 
-    fn number(&mut self) {
-        while self.peek().is_ascii_digit() {
-            self.advance();
-        }
+    /// Scans a number literal, where `_` may separate digits for readability, e.g. `1_000_000`.
+    fn number(&mut self) -> Result<(), ScanError> {
+        self.digits();
 
         // Look for fractional part '.'
-        if self.peek() == '.' && self.peek_next().is_ascii_digit() {
+        if self.peek() == '.' && (self.peek_next().is_ascii_digit() || self.peek_next() == '_') {
             self.advance();
+            self.digits();
+        }
 
-            while self.peek().is_ascii_digit() {
-                self.advance();
-            }
+        // Separators are only allowed between two digits
+        let lexeme = self.substring(self.start, self.current);
+        if lexeme
+            .split('.')
+            .any(|part| part.starts_with('_') || part.ends_with('_') || part.contains("__"))
+        {
+            return Err(self.error_at_current("misplaced digit separator '_'"));
         }
 
         // Get the value and parse it as a string.
-        let text = &self.source[self.start..self.current];
-        let value = text.parse::<f64>().expect("Failed to parse number");
+        // Literals without a fractional part are kept as integers, unless they overflow `i64`.
+        let text = lexeme.replace('_', "");
+        let literal = match text.parse::<i64>() {
+            Ok(value) => Literal::Integer(value),
+            Err(_) => Literal::Number(text.parse::<f64>().expect("Failed to parse number")),
+        };
+
+        // Every number is an `f64` at runtime, so larger integers can't be represented exactly
+        let is_safe_integer = matches!(literal, Literal::Integer(n) if n <= Self::MAX_SAFE_INTEGER);
+        if !text.contains('.') && !is_safe_integer {
+            let warning =
+                self.warning_at_current("integer literal exceeds 2^53 and may lose precision");
+            self.warnings.push(warning);
+        }
 
-        self.add_token_literal(TokenType::Number, Some(Literal::Number(value)));
+        self.add_token_literal(TokenType::Number, Some(literal));
+
+        Ok(())
+    }
+
+    fn digits(&mut self) {
+        while self.peek().is_ascii_digit() || self.peek() == '_' {
+            self.advance();
+        }
     }
 
     fn identifier(&mut self) {
-        while self.peek().is_ascii_alphanumeric() {
+        while self.peek().is_ascii_alphanumeric() || self.peek() == '_' {
             self.advance();
         }
 
-        let text = &self.source[self.start..self.current];
+        let text = self.substring(self.start, self.current);
         let token_type = keywords()
-            .get(text)
+            .get(text.as_str())
+            .or_else(|| self.extra_keywords.get(&text))
             .cloned()
             .unwrap_or(TokenType::Identifier);
 
         self.add_token(token_type);
     }
 
+    /// Collects the characters between two indices into the source.
+    fn substring(&self, start: usize, end: usize) -> String {
+        self.source[start..end].iter().collect()
+    }
+
+    /// The 1-based column the current token starts at, counted on the line it starts on.
+    fn start_column(&self) -> usize {
+        self.start - self.token_line_start + 1
+    }
+
     fn error_at_current(&self, message: impl Into<String>) -> ScanError {
-        let lexeme = self.source[self.start..self.current.min(self.source.len())].to_string();
+        let lexeme = self.substring(self.start, self.current.min(self.source.len()));
 
         ScanError {
             message: message.into(),
             context: ErrorContext {
                 line_number: self.line,
+                column: self.start_column(),
+                lexeme,
+            },
+        }
+    }
+
+    fn warning_at_current(&self, message: impl Into<String>) -> Warning {
+        let lexeme = self.substring(self.start, self.current.min(self.source.len()));
+
+        Warning {
+            message: message.into(),
+            context: ErrorContext {
+                line_number: self.line,
+                column: self.start_column(),
                 lexeme,
             },
         }
     }
 
     fn error_at_line(&self, message: impl Into<String>, line: usize) -> ScanError {
-        let lexeme = self.source[self.start..self.current.min(self.source.len())].to_string();
+        let lexeme = self.substring(self.start, self.current.min(self.source.len()));
 
         ScanError {
             message: message.into(),
             context: ErrorContext {
                 line_number: line,
+                column: self.start_column(),
                 lexeme,
             },
         }
@@ -300,10 +554,38 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn scan_percent() {
+        // Arrange
+        let source = "7 % 3";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[1].token_type, TokenType::Percent);
+        assert_eq!(tokens[1].lexeme, "%");
+    }
+
+    #[test]
+    fn scan_decorator_sigil() {
+        // Arrange
+        let source = "@foo";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        let expected = vec![TokenType::At, TokenType::Identifier, TokenType::Eof];
+        let actual: Vec<TokenType> = tokens.iter().map(|t| t.token_type.clone()).collect();
+
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn scan_operators() {
         // Arrange
-        let source = "! != = == > >= < <=";
+        let source = "! != !== = == === > >= < <=";
 
         // Act
         let tokens = Scanner::new(source).tokenize().unwrap();
@@ -312,8 +594,10 @@ mod tests {
         let expected = vec![
             TokenType::Bang,
             TokenType::BangEqual,
+            TokenType::BangEqualEqual,
             TokenType::Equal,
             TokenType::EqualEqual,
+            TokenType::EqualEqualEqual,
             TokenType::Greater,
             TokenType::GreaterEqual,
             TokenType::Less,
@@ -361,6 +645,72 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn scan_nested_multiline_comment() {
+        // Arrange
+        let source = "/* outer /* inner */ still\n commented */ x";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].token_type, TokenType::Identifier);
+        assert_eq!(tokens[0].line, 2);
+        assert_eq!(tokens[1].token_type, TokenType::Eof);
+    }
+
+    #[test]
+    fn scan_unterminated_nested_multiline_comment() {
+        // Arrange
+        let mut scanner = Scanner::new("/* outer\n /* inner */ never closed");
+
+        // Act
+        let error = scanner.scan_token().unwrap_err();
+
+        // Assert
+        assert_eq!(error.message, "unterminated multi-line comment");
+        assert_eq!(error.context.line_number, 1);
+    }
+
+    #[test]
+    fn scan_backslash_line_continuation() {
+        // Arrange
+        let source = "var total = 1 + \\\n  2 + \\\r\n  3;";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        let actual: Vec<(&str, usize)> =
+            tokens.iter().map(|t| (t.lexeme.as_str(), t.line)).collect();
+        let expected = vec![
+            ("var", 1),
+            ("total", 1),
+            ("=", 1),
+            ("1", 1),
+            ("+", 1),
+            ("2", 2),
+            ("+", 2),
+            ("3", 3),
+            (";", 3),
+            ("", 3),
+        ];
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn scan_stray_backslash_returns_none() {
+        // Arrange
+        let source = "1 \\ 2";
+
+        // Act
+        let result = Scanner::new(source).tokenize();
+
+        // Assert
+        assert!(result.is_none());
+    }
+
     #[test]
     fn scan_string_literal() {
         let source = r#""hello""#;
@@ -377,6 +727,143 @@ mod tests {
         );
     }
 
+    #[test]
+    fn scan_string_escape_sequences() {
+        // Arrange
+        let source = r#""\n\t\r\"\\\0\$""#;
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].lexeme, source);
+        assert_eq!(
+            tokens[0].literal,
+            Some(Literal::String("\n\t\r\"\\\0$".to_string()))
+        );
+    }
+
+    #[test]
+    fn scan_unknown_escape_sequence() {
+        // Arrange
+        let mut scanner = Scanner::new("\n\"a\\qb\"");
+        scanner.scan_token().unwrap();
+
+        // Act
+        let error = scanner.scan_token().unwrap_err();
+
+        // Assert
+        assert_eq!(error.message, "unknown escape sequence '\\q'");
+        assert_eq!(error.context.lexeme, "\\q");
+        assert_eq!(error.context.line_number, 2);
+        assert!(scanner.is_at_end());
+    }
+
+    #[test]
+    fn scan_raw_string_literal_keeps_backslashes() {
+        // Arrange
+        let source = r#"r"a\nb""#;
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].token_type, TokenType::String);
+        assert_eq!(tokens[0].lexeme, r#"r"a\nb""#);
+        assert_eq!(
+            tokens[0].literal,
+            Some(Literal::String(r"a\nb".to_string()))
+        );
+    }
+
+    #[test]
+    fn scan_multiline_raw_string_literal() {
+        // Arrange
+        let source = "r\"a\nb\" c";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].literal, Some(Literal::String("a\nb".to_string())));
+        assert_eq!(tokens[1].line, 2);
+    }
+
+    #[test]
+    fn scan_columns_around_multiline_tokens() {
+        // Arrange
+        let source = "x = \"a\nbc\" + y; /* d\n */ z";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        let columns: Vec<(usize, usize)> = tokens.iter().map(|t| (t.line, t.column)).collect();
+        // A multi-line token reports the column it starts at, later tokens count from the last newline
+        assert_eq!(
+            columns,
+            [
+                (1, 1),
+                (1, 3),
+                (2, 5),
+                (2, 5),
+                (2, 7),
+                (2, 8),
+                (3, 5),
+                (3, 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn scan_string_interpolation() {
+        // Arrange
+        let source = r#""sum = ${a + b}!""#;
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        let expected = vec![
+            TokenType::Interpolation,
+            TokenType::Identifier,
+            TokenType::Plus,
+            TokenType::Identifier,
+            TokenType::String,
+            TokenType::Eof,
+        ];
+        let actual: Vec<TokenType> = tokens.iter().map(|t| t.token_type.clone()).collect();
+
+        assert_eq!(expected, actual);
+        assert_eq!(tokens[0].literal, Some(Literal::String("sum = ".into())));
+        assert_eq!(tokens[4].literal, Some(Literal::String("!".into())));
+    }
+
+    #[test]
+    fn scan_escaped_dollar_is_not_interpolated() {
+        // Arrange
+        let source = r#""\${5}""#;
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].token_type, TokenType::String);
+        assert_eq!(tokens[0].literal, Some(Literal::String("${5}".into())));
+    }
+
+    #[test]
+    fn scan_unterminated_interpolation_returns_none() {
+        // Arrange
+        let source = r#""a ${b"#;
+
+        // Act
+        let result = Scanner::new(source).tokenize();
+
+        // Assert
+        assert!(result.is_none());
+    }
+
     #[test]
     fn scan_unterminated_string_literal_returns_none() {
         // Arrange
@@ -403,6 +890,161 @@ mod tests {
         assert_eq!(tokens[0].literal, Some(Literal::Number(123.45)));
     }
 
+    #[test]
+    fn scan_integer_literal() {
+        // Arrange
+        let source = "42";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].token_type, TokenType::Number);
+        assert_eq!(tokens[0].literal, Some(Literal::Integer(42)));
+    }
+
+    #[test]
+    fn scan_float_literal() {
+        // Arrange
+        let source = "42.0";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].token_type, TokenType::Number);
+        assert_eq!(tokens[0].literal, Some(Literal::Number(42.0)));
+    }
+
+    #[test]
+    fn scan_number_literals_with_digit_separators() {
+        // Arrange
+        let source = "1_000_000 1.234_567";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].lexeme, "1_000_000");
+        assert_eq!(tokens[0].literal, Some(Literal::Integer(1_000_000)));
+        assert_eq!(tokens[1].literal, Some(Literal::Number(1.234_567)));
+    }
+
+    #[test]
+    fn scan_misplaced_digit_separators() {
+        for source in ["1__0", "1_", "1_.0", "1._0", "1.0_"] {
+            // Arrange
+            let mut scanner = Scanner::new(source);
+
+            // Act
+            let error = scanner.scan_token().unwrap_err();
+
+            // Assert
+            assert_eq!(error.message, "misplaced digit separator '_'");
+            assert_eq!(error.context.lexeme, source);
+        }
+    }
+
+    #[test]
+    fn scan_leading_underscore_is_identifier() {
+        // Arrange
+        let source = "_1";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].token_type, TokenType::Identifier);
+    }
+
+    #[test]
+    fn scan_integer_literal_beyond_two_pow_53_warns() {
+        // Arrange
+        let mut scanner = Scanner::new("9007199254740993");
+
+        // Act
+        scanner.scan_tokens();
+
+        // Assert
+        assert_eq!(scanner.warnings.len(), 1);
+        assert_eq!(scanner.warnings[0].context.lexeme, "9007199254740993");
+    }
+
+    #[test]
+    fn scan_small_integer_literal_does_not_warn() {
+        // Arrange
+        let mut scanner = Scanner::new("1000");
+
+        // Act
+        scanner.scan_tokens();
+
+        // Assert
+        assert!(scanner.warnings.is_empty());
+    }
+
+    #[test]
+    fn scan_trailing_whitespace_warns_per_line() {
+        // Arrange
+        let mut scanner =
+            Scanner::new("var a = 1;  \nprint a;\nprint \"é\";\t \r\n").warn_trailing_whitespace();
+
+        // Act
+        scanner.scan_tokens();
+
+        // Assert
+        let lines: Vec<(usize, usize, &str)> = scanner
+            .warnings
+            .iter()
+            .map(|w| {
+                (
+                    w.context.line_number,
+                    w.context.column,
+                    w.context.lexeme.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(lines, [(1, 11, "  "), (3, 11, "\t ")]);
+    }
+
+    #[test]
+    fn scan_trailing_whitespace_does_not_change_tokens() {
+        // Arrange
+        let source = "var a = 1;  \nprint a;";
+
+        // Act
+        let linted = Scanner::new(source).warn_trailing_whitespace().tokenize();
+        let plain = Scanner::new(source).tokenize();
+
+        // Assert
+        assert_eq!(linted, plain);
+    }
+
+    #[test]
+    fn scan_clean_lines_do_not_warn_about_whitespace() {
+        // Arrange
+        let mut scanner = Scanner::new("var a = 1;\r\n  print a;\n").warn_trailing_whitespace();
+
+        // Act
+        scanner.scan_tokens();
+
+        // Assert
+        assert!(scanner.warnings.is_empty());
+    }
+
+    #[test]
+    fn scan_identifier_with_underscores() {
+        // Arrange
+        let source = "round_half_even _x1_";
+
+        // Act
+        let tokens = Scanner::new(source).tokenize().unwrap();
+
+        // Assert
+        let lexemes: Vec<&str> = tokens[..2].iter().map(|t| t.lexeme.as_str()).collect();
+        assert_eq!(lexemes, ["round_half_even", "_x1_"]);
+        assert_eq!(tokens[2].token_type, TokenType::Eof);
+    }
+
     #[test]
     fn scan_keywords() {
         // Arrange
@@ -421,6 +1063,26 @@ mod tests {
         assert_eq!(tokens[1].literal, None);
     }
 
+    #[test]
+    fn scan_custom_keyword() {
+        // Arrange
+        let extra = HashMap::from([
+            ("unless".to_string(), TokenType::Custom("unless".into())),
+            ("print".to_string(), TokenType::Custom("print".into())),
+        ]);
+
+        // Act
+        let tokens = Scanner::new("unless print")
+            .with_keywords(extra)
+            .tokenize()
+            .unwrap();
+
+        // Assert
+        assert_eq!(tokens[0].token_type, TokenType::Custom("unless".into()));
+        assert_eq!(tokens[0].lexeme, "unless");
+        assert_eq!(tokens[1].token_type, TokenType::Print);
+    }
+
     #[test]
     fn ignore_whitespace() {
         // Arrange
@@ -433,6 +1095,42 @@ mod tests {
         assert_eq!(tokens[0].token_type, TokenType::Eof);
     }
 
+    #[test]
+    fn tab_indentation_is_ignored_by_default() {
+        // Arrange
+        let source = "{\n\tprint 1;\n}";
+
+        // Act
+        let result = Scanner::new(source).tokenize();
+
+        // Assert
+        assert!(result.is_some());
+    }
+
+    #[test]
+    fn tab_indentation_is_rejected_when_tabs_are_forbidden() {
+        // Arrange
+        let source = "{\n\tprint 1;\n}";
+
+        // Act
+        let result = Scanner::new(source).forbid_tabs().tokenize();
+
+        // Assert
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn tab_after_code_is_allowed_when_tabs_are_forbidden() {
+        // Arrange
+        let source = "print 1;\t// trailing comment";
+
+        // Act
+        let result = Scanner::new(source).forbid_tabs().tokenize();
+
+        // Assert
+        assert!(result.is_some());
+    }
+
     #[test]
     fn scan_end_of_file() {
         // Arrange
@@ -450,7 +1148,7 @@ mod tests {
     #[test]
     fn scan_invalid_character_returns_none() {
         // Arrange
-        let source = "@";
+        let source = "#";
 
         // Act
         let result = Scanner::new(source).tokenize();
@@ -0,0 +1,24 @@
+use crate::common::error_context::{ErrorContext, PrettyError};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct ResolveError {
+    pub message: String,
+    pub context: ErrorContext,
+}
+
+impl PrettyError for ResolveError {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn context(&self) -> &ErrorContext {
+        &self.context
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.pretty_fmt(f)
+    }
+}
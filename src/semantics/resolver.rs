@@ -0,0 +1,447 @@
+use crate::ast::{Expr, ExprId, InterpPart, Stmt};
+use crate::codegen::NATIVES;
+use crate::common::Token;
+use crate::common::error_context::ErrorContext;
+use crate::semantics::resolve_error::ResolveError;
+use std::collections::{HashMap, HashSet};
+
+/// Where a variable access finds its variable, as resolved before the program runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Binding {
+    /// Declared this many scopes up from its use.
+    Local(usize),
+    Global,
+}
+
+/// The binding of each variable access, keyed by the accessing expression.
+/// Expressions that aren't in the table were never resolved.
+pub type Locals = HashMap<ExprId, Binding>;
+
+/// Statically binds each variable access to the scope it refers to, before the program runs.
+/// This keeps a closure referring to the variable that was in scope where it was declared,
+/// even if a later declaration in an enclosing block shadows it.
+#[derive(Default)]
+pub struct Resolver {
+    /// The local scopes enclosing the current statement, innermost last.
+    /// A variable maps to whether its initializer has been resolved yet.
+    scopes: Vec<HashMap<String, bool>>,
+    /// Every name declared at the top level, so globals can be used before their declaration.
+    globals: HashSet<String>,
+    locals: Locals,
+    errors: Vec<ResolveError>,
+    /// How many class declarations enclose the current statement, `this` is only valid inside one.
+    class_depth: usize,
+    /// Whether the innermost enclosing function is a class's `init` method.
+    in_initializer: bool,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            globals: NATIVES.iter().map(|native| native.name().into()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Resolves the whole program, returning the variable bindings alongside
+    /// any variables that aren't declared anywhere in scope.
+    pub fn resolve(mut self, statements: &[Stmt]) -> (Locals, Vec<ResolveError>) {
+        self.globals
+            .extend(statements.iter().filter_map(|stmt| match stmt {
+                Stmt::Class { name, .. } | Stmt::Function { name, .. } | Stmt::Var { name, .. } => {
+                    Some(name.lexeme.clone())
+                }
+                _ => None,
+            }));
+
+        self.resolve_statements(statements);
+        (self.locals, self.errors)
+    }
+
+    fn resolve_statements(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            self.resolve_stmt(stmt);
+        }
+    }
+
+    fn resolve_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Assert { condition, .. } => self.resolve_expr(condition),
+            Stmt::Block { statements } => {
+                self.scopes.push(HashMap::new());
+                self.resolve_statements(statements);
+                self.scopes.pop();
+            }
+            Stmt::Class { name, methods } => {
+                self.declare(name);
+                self.define(name);
+
+                // Methods are bound to their instance in a scope of their own, holding only `this`
+                self.class_depth += 1;
+                self.scopes
+                    .push(HashMap::from([("this".to_string(), true)]));
+                for method in methods {
+                    if let Stmt::Function {
+                        name,
+                        parameters,
+                        body,
+                    } = method
+                    {
+                        self.resolve_function(parameters, body, name.lexeme == "init");
+                    }
+                }
+                self.scopes.pop();
+                self.class_depth -= 1;
+            }
+            Stmt::Expression { expression } | Stmt::Print { expression } => {
+                self.resolve_expr(expression)
+            }
+            Stmt::Function {
+                name,
+                parameters,
+                body,
+            } => {
+                // Defined right away, so the function can refer to itself recursively
+                self.declare(name);
+                self.define(name);
+                self.resolve_function(parameters, body, false);
+            }
+            Stmt::Printf {
+                format, arguments, ..
+            } => {
+                self.resolve_expr(format);
+                for argument in arguments {
+                    self.resolve_expr(argument);
+                }
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_stmt(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.resolve_stmt(else_branch);
+                }
+            }
+            Stmt::Return { keyword, value } => {
+                if let Some(value) = value {
+                    // An initializer always returns its instance
+                    if self.in_initializer {
+                        self.errors
+                            .push(error("can't return a value from an initializer", keyword));
+                    }
+
+                    self.resolve_expr(value);
+                }
+            }
+            Stmt::Var { name, initializer } => {
+                self.declare(name);
+                if let Some(initializer) = initializer {
+                    self.resolve_expr(initializer);
+                }
+                self.define(name);
+            }
+            Stmt::While { condition, body } => {
+                self.resolve_expr(condition);
+                self.resolve_stmt(body);
+            }
+        }
+    }
+
+    fn resolve_function(&mut self, parameters: &[Token], body: &[Stmt], is_initializer: bool) {
+        let enclosing_initializer = std::mem::replace(&mut self.in_initializer, is_initializer);
+
+        // Parameters and body share the scope the function is called in
+        self.scopes.push(HashMap::new());
+        for parameter in parameters {
+            self.declare(parameter);
+            self.define(parameter);
+        }
+        self.resolve_statements(body);
+        self.scopes.pop();
+
+        self.in_initializer = enclosing_initializer;
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Assign { id, name, value } => {
+                self.resolve_expr(value);
+                self.resolve_local(*id, name);
+            }
+            Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+                self.resolve_expr(left);
+                self.resolve_expr(right);
+            }
+            Expr::Call {
+                callee, arguments, ..
+            } => {
+                self.resolve_expr(callee);
+                for argument in arguments {
+                    self.resolve_expr(argument);
+                }
+            }
+            Expr::Get { object, .. } => self.resolve_expr(object),
+            Expr::Grouping { expression } => self.resolve_expr(expression),
+            Expr::Interpolation { parts } => {
+                for part in parts {
+                    if let InterpPart::Expr(expr) = part {
+                        self.resolve_expr(expr);
+                    }
+                }
+            }
+            Expr::Set { object, value, .. } => {
+                self.resolve_expr(value);
+                self.resolve_expr(object);
+            }
+            Expr::This { id, keyword } => {
+                if self.class_depth == 0 {
+                    self.errors
+                        .push(error("can't use 'this' outside of a class", keyword));
+                    return;
+                }
+
+                self.resolve_local(*id, keyword)
+            }
+            Expr::Unary { right, .. } => self.resolve_expr(right),
+            Expr::Variable { id, name } => {
+                // Declared but not yet defined means we're inside its initializer, e.g. `var a = a;`
+                if self.scopes.last().and_then(|scope| scope.get(&name.lexeme)) == Some(&false) {
+                    self.errors.push(error(
+                        "can't read local variable in its own initializer",
+                        name,
+                    ));
+                }
+
+                self.resolve_local(*id, name)
+            }
+            Expr::Literal { .. } => {}
+        }
+    }
+
+    fn declare(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), false);
+        }
+    }
+
+    fn define(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), true);
+        }
+    }
+
+    fn resolve_local(&mut self, id: ExprId, name: &Token) {
+        let depth = self
+            .scopes
+            .iter()
+            .rev()
+            .position(|scope| scope.contains_key(&name.lexeme));
+
+        let binding = match depth {
+            Some(depth) => Binding::Local(depth),
+            None => {
+                if !self.globals.contains(&name.lexeme) {
+                    self.errors.push(error("undefined variable", name));
+                }
+                Binding::Global
+            }
+        };
+        self.locals.insert(id, binding);
+    }
+}
+
+fn error(message: &str, token: &Token) -> ResolveError {
+    ResolveError {
+        message: message.into(),
+        context: ErrorContext {
+            line_number: token.line,
+            column: token.column,
+            lexeme: token.lexeme.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+    use crate::scanner::Scanner;
+
+    fn resolve_with_errors(source: &str) -> (Locals, Vec<ResolveError>) {
+        let tokens = Scanner::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        Resolver::new().resolve(&statements)
+    }
+
+    fn resolve(source: &str) -> Locals {
+        let (locals, errors) = resolve_with_errors(source);
+        assert!(errors.is_empty());
+        locals
+    }
+
+    #[test]
+    fn resolve_global_is_bound_globally() {
+        // Arrange
+        let source = "var a = 1; print a;";
+
+        // Act
+        let locals = resolve(source);
+
+        // Assert
+        let bindings: Vec<Binding> = locals.into_values().collect();
+        assert_eq!(bindings, [Binding::Global]);
+    }
+
+    #[test]
+    fn resolve_local_in_enclosing_scope() {
+        // Arrange
+        let source = "{ var a = 1; { { print a; } } }";
+
+        // Act
+        let locals = resolve(source);
+
+        // Assert
+        let bindings: Vec<Binding> = locals.into_values().collect();
+        assert_eq!(bindings, [Binding::Local(2)]);
+    }
+
+    #[test]
+    fn resolve_parameter_in_function_scope() {
+        // Arrange
+        let source = "fun f(a) { a = a + 1; }";
+
+        // Act
+        let locals = resolve(source);
+
+        // Assert
+        let bindings: Vec<Binding> = locals.into_values().collect();
+        assert_eq!(bindings, [Binding::Local(0), Binding::Local(0)]);
+    }
+
+    #[test]
+    fn resolve_undefined_local_errors() {
+        // Arrange
+        let source = "{ var a = 1; print b; }";
+
+        // Act
+        let (_, errors) = resolve_with_errors(source);
+
+        // Assert
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "undefined variable");
+        assert_eq!(errors[0].context.lexeme, "b");
+    }
+
+    #[test]
+    fn resolve_variable_out_of_its_block_errors() {
+        // Arrange
+        let source = "{ var a = 1; }\nprint a;";
+
+        // Act
+        let (_, errors) = resolve_with_errors(source);
+
+        // Assert
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].context.line_number, 2);
+    }
+
+    #[test]
+    fn resolve_forward_reference_to_global_function() {
+        // Arrange
+        let source = "fun first() { return second(); }\nfun second() { return 2; }\nprint first();";
+
+        // Act
+        let (_, errors) = resolve_with_errors(source);
+
+        // Assert
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn resolve_local_read_in_own_initializer_errors() {
+        // Arrange
+        let source = "var a = 1;\n{ var a = a; }";
+
+        // Act
+        let (_, errors) = resolve_with_errors(source);
+
+        // Assert
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "can't read local variable in its own initializer"
+        );
+        assert_eq!(errors[0].context.line_number, 2);
+        assert_eq!(errors[0].context.column, 11);
+    }
+
+    #[test]
+    fn resolve_local_initialized_from_outer_variable() {
+        // Arrange
+        let source = "var b = 1;\n{ var a = b; }";
+
+        // Act
+        let (_, errors) = resolve_with_errors(source);
+
+        // Assert
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn resolve_this_in_method_as_enclosing_scope() {
+        // Arrange
+        let source = "class A { f() { return this; } }";
+
+        // Act
+        let locals = resolve(source);
+
+        // Assert
+        let bindings: Vec<Binding> = locals.into_values().collect();
+        assert_eq!(bindings, [Binding::Local(1)]);
+    }
+
+    #[test]
+    fn resolve_this_outside_class_errors() {
+        // Arrange
+        let source = "fun f() { return this; }";
+
+        // Act
+        let (_, errors) = resolve_with_errors(source);
+
+        // Assert
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "can't use 'this' outside of a class");
+    }
+
+    #[test]
+    fn resolve_value_return_in_initializer_errors() {
+        // Arrange
+        let source = "class A {\n  init() { return 1; }\n  f() { return 2; }\n}";
+
+        // Act
+        let (_, errors) = resolve_with_errors(source);
+
+        // Assert
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "can't return a value from an initializer"
+        );
+        assert_eq!(errors[0].context.line_number, 2);
+    }
+
+    #[test]
+    fn resolve_value_return_in_function_nested_in_initializer() {
+        // Arrange
+        let source = "class A { init() { fun f() { return 1; } return; } }";
+
+        // Act
+        let (_, errors) = resolve_with_errors(source);
+
+        // Assert
+        assert!(errors.is_empty());
+    }
+}
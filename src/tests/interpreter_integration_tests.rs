@@ -0,0 +1,1121 @@
+#![cfg(test)]
+
+use crate::ast::Stmt;
+use crate::codegen::interpreter::Interpreter;
+use crate::parser::parser::Parser;
+use crate::scanner::Scanner;
+use crate::semantics::resolver::Resolver;
+use std::cell::RefCell;
+use std::io;
+use std::io::Write;
+use std::rc::Rc;
+
+/// An output sink that can still be read after being handed to the interpreter.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn parse(source: &str) -> Vec<Stmt> {
+    let tokens = Scanner::new(source).tokenize().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+/// An interpreter for the source with its variables resolved, as `main` runs programs.
+fn resolved(source: &str) -> Interpreter {
+    let statements = parse(source);
+    let (locals, errors) = Resolver::new().resolve(&statements);
+    assert!(errors.is_empty());
+    Interpreter::new(statements).with_locals(locals)
+}
+
+#[test]
+fn print_writes_to_injected_output() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let mut interpreter = Interpreter::new(parse("print 1 + 2;")).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "3\n");
+}
+
+#[test]
+fn quiet_mode_suppresses_prints_but_reports_errors() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print \"hidden\";\nprint -\"oops\";";
+
+    // Act
+    let quiet = Interpreter::new(parse(source))
+        .with_output(io::sink())
+        .interpret();
+    let result = Interpreter::new(parse(source))
+        .with_output(output.clone())
+        .interpret();
+
+    // Assert
+    // Errors don't go through the output, so discarding it as `--quiet` does still reports them
+    assert_eq!(output.contents(), "hidden\n");
+    assert_eq!(quiet.unwrap_err().context.line_number, 2);
+    assert_eq!(result.unwrap_err().context.line_number, 2);
+}
+
+#[test]
+fn reset_drops_user_definitions() {
+    // Arrange
+    let mut interpreter = Interpreter::new(vec![]);
+    for stmt in parse("var a = 1;") {
+        interpreter.execute(&stmt).unwrap();
+    }
+
+    // Act
+    interpreter.reset();
+
+    // Assert
+    let result = interpreter.execute(&parse("print a;")[0]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn restore_rolls_back_to_snapshot() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let mut interpreter = Interpreter::new(vec![]).with_output(output.clone());
+    interpreter.execute(&parse("var a = 1;")[0]).unwrap();
+    let snapshot = interpreter.snapshot();
+    interpreter.execute(&parse("var a = 2;")[0]).unwrap();
+
+    // Act
+    interpreter.restore(snapshot);
+
+    // Assert
+    interpreter.execute(&parse("print a;")[0]).unwrap();
+    assert_eq!(output.contents(), "1\n");
+}
+
+#[test]
+fn xor_truth_table() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source =
+        "print true xor true;\nprint true xor false;\nprint false xor true;\nprint nil xor false;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "false\ntrue\ntrue\nfalse\n");
+}
+
+#[test]
+fn strict_equality_distinguishes_types() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print 1 === \"1\";\nprint 1 === 1;\nprint nil !== false;\nprint \"a\" !== \"a\";";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "false\ntrue\ntrue\nfalse\n");
+}
+
+#[test]
+fn print_respects_precision() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let mut interpreter = Interpreter::new(parse("print 3.14159;\nprint 1000;"))
+        .with_output(output.clone())
+        .with_precision(3);
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "3.14\n1000\n");
+}
+
+#[test]
+fn interpolation_respects_precision() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let mut interpreter = Interpreter::new(parse("var x = 3.14159;\nprint \"x = ${x}\";"))
+        .with_output(output.clone())
+        .with_precision(3);
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "x = 3.14\n");
+}
+
+#[test]
+fn counted_loop_sums_large_range() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source =
+        "var sum = 0;\nfor (var i = 0; i < 100000; i = i + 1) { sum = sum + i; }\nprint sum;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "4999950000\n");
+}
+
+#[test]
+fn counted_loop_matches_general_loop() {
+    // Arrange
+    let counted = SharedBuffer::default();
+    let general = SharedBuffer::default();
+    let body = "{ var j = i * 2; print j; }\nprint i;";
+    let counted_source = format!("var i = 10;\nwhile (i >= 0.5) {{ {body} i = i - 3; }}");
+    let general_source =
+        format!("var i = 10; var b = 0.5;\nwhile (i >= b) {{ {body} i = i - 3; }}");
+
+    // Act
+    Interpreter::new(parse(&counted_source))
+        .with_output(counted.clone())
+        .interpret()
+        .unwrap();
+    Interpreter::new(parse(&general_source))
+        .with_output(general.clone())
+        .interpret()
+        .unwrap();
+
+    // Assert
+    assert_eq!(counted.contents(), general.contents());
+}
+
+#[test]
+fn counted_loop_steps_the_resolved_counter() {
+    // Arrange
+    let counted = SharedBuffer::default();
+    let general = SharedBuffer::default();
+    // `f` steps the global `i`, not the local declared after it in the same block
+    let program = |bound: &str| {
+        format!(
+            "var i = 0; var b = 3;\n{{\n  fun f() {{ while (i < {bound}) {{ i = i + 1; }} }}\n  var i = 100;\n  f();\n  print i;\n}}\nprint i;"
+        )
+    };
+
+    // Act
+    resolved(&program("3"))
+        .with_output(counted.clone())
+        .interpret()
+        .unwrap();
+    resolved(&program("b"))
+        .with_output(general.clone())
+        .interpret()
+        .unwrap();
+
+    // Assert
+    assert_eq!(counted.contents(), "100\n3\n");
+    assert_eq!(counted.contents(), general.contents());
+}
+
+#[test]
+fn loop_assigning_counter_in_body_is_not_counted() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "for (var i = 0; i < 10; i = i + 1) { if (i == 2) i = 8; print i; }";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "0\n1\n8\n9\n");
+}
+
+#[test]
+fn elif_chain_runs_matching_branch() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "for (var i = 0; i < 4; i = i + 1) {
+        if (i == 0) print \"zero\";
+        elif (i == 1) print \"one\";
+        elif (i == 2) print \"two\";
+        else print \"many\";
+    }";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "zero\none\ntwo\nmany\n");
+}
+
+#[test]
+fn string_interpolation_evaluates_embedded_expressions() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = r#"var a = 1;
+var b = 2;
+print "sum = ${a + b}";
+print "${a}${b}";
+print "nested ${"inner ${a}"} done";
+print "\$5";"#;
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "sum = 3\n12\nnested inner 1 done\n$5\n");
+}
+
+#[test]
+fn multi_line_binary_error_reports_offending_operand_line() {
+    // Arrange
+    let source = "print 1\n  + 2\n  + \"a\"\n  + 3;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(io::sink());
+
+    // Act
+    let error = interpreter.interpret().unwrap_err();
+
+    // Assert
+    assert_eq!(error.context.line_number, 3);
+    assert_eq!(error.context.lexeme, "+");
+}
+
+#[test]
+fn block_declarations_do_not_leak() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source =
+        "var a = \"outer\";\n{ var a = \"inner\"; var b = 1; { print a; } }\nprint a;\nprint b;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(output.contents(), "inner\nouter\n");
+    assert_eq!(result.unwrap_err().message, "undefined variable");
+}
+
+#[test]
+fn modulo_evaluates_remainder() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let mut interpreter =
+        Interpreter::new(parse("print 7 % 3 == 1;\nprint -7 % 3;\nprint \"7\" % 3;"))
+            .with_output(output.clone());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(output.contents(), "true\n-1\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn function_declaration_is_stored() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let mut interpreter = Interpreter::new(parse("fun greet(name) { print name; }\nprint greet;"))
+        .with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "<fn greet>\n");
+}
+
+#[test]
+fn calling_a_function_runs_its_body() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "fun add(a, b) { print a + b; }\nadd(1, 2);\nadd(\"a\", \"b\");";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "3\nab\n");
+}
+
+#[test]
+fn calling_with_wrong_argument_count_errors() {
+    // Arrange
+    let mut interpreter = Interpreter::new(parse("fun f(a) {}\nf(1, 2);")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "expected 1 arguments but got 2"
+    );
+}
+
+#[test]
+fn calling_a_non_function_errors() {
+    // Arrange
+    let mut interpreter = Interpreter::new(parse("\"text\"();")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "can only call functions and classes"
+    );
+}
+
+#[test]
+fn return_exits_function_early() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "fun sign(n) {\n  if (n < 0) { return -1; }\n  print \"not negative\";\n  return 1;\n}\nprint sign(-5);\nprint sign(5);";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "-1\nnot negative\n1\n");
+}
+
+#[test]
+fn return_unwinds_out_of_loop() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "fun firstOver(limit) {\n  var i = 0;\n  while (true) {\n    { if (i * i > limit) return i; }\n    i = i + 1;\n  }\n}\nprint firstOver(50);\nvar i = \"global\";\nprint i;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "8\nglobal\n");
+}
+
+#[test]
+fn function_without_return_value_returns_nil() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "fun implicit() {}\nfun bare() { return; print \"unreachable\"; }\nprint implicit();\nprint bare();";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "nil\nnil\n");
+}
+
+#[test]
+fn closure_keeps_state_across_calls() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "fun makeCounter() {\n  var count = 0;\n  fun increment() {\n    count = count + 1;\n    return count;\n  }\n  return increment;\n}\nvar a = makeCounter();\nvar b = makeCounter();\nprint a();\nprint a();\nprint b();";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "1\n2\n1\n");
+}
+
+#[test]
+fn function_declared_in_block_captures_locals_and_does_not_leak() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source =
+        "{\n  var greeting = \"hi\";\n  fun greet() { print greeting; }\n  greet();\n}\ngreet();";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(output.contents(), "hi\n");
+    assert_eq!(result.unwrap_err().message, "undefined variable");
+}
+
+#[test]
+fn call_profiling_counts_calls_per_function() {
+    // Arrange
+    let source = "fun a() {}\nfun b() { a(); }\na();\nb();\nb();";
+    let mut interpreter = Interpreter::new(parse(source)).with_call_profiling();
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    let call_counts = interpreter.call_counts().unwrap();
+    assert_eq!(call_counts["a"], 3);
+    assert_eq!(call_counts["b"], 2);
+}
+
+#[test]
+fn call_counts_are_not_tracked_by_default() {
+    // Arrange
+    let mut interpreter = Interpreter::new(parse("fun a() {}\na();"));
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert!(interpreter.call_counts().is_none());
+}
+
+#[test]
+fn closure_keeps_resolving_to_the_variable_in_scope_at_declaration() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "var a = \"global\";\n{\n  fun show() { print a; }\n  show();\n  var a = \"block\";\n  show();\n  print a;\n}";
+    let mut interpreter = resolved(source).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "global\nglobal\nblock\n");
+}
+
+#[test]
+fn unresolved_statements_look_up_variables_in_enclosing_scopes() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let mut interpreter = Interpreter::new(vec![]).with_output(output.clone());
+
+    // Act
+    for stmt in parse("{ var a = 1; { a = a + 1; print a; } }") {
+        interpreter.execute(&stmt).unwrap();
+    }
+
+    // Assert
+    assert_eq!(output.contents(), "2\n");
+}
+
+#[test]
+fn printf_fills_placeholders_in_order() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "var name = \"x\";\nprintf \"{}={} ({})\", name, 1 + 2, true;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "x=3 (true)\n");
+}
+
+#[test]
+fn printf_argument_count_mismatch_errors() {
+    // Arrange
+    let mut interpreter =
+        Interpreter::new(parse("printf \"{} and {}\", 1;")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "format expects 2 arguments but got 1"
+    );
+}
+
+#[test]
+fn printf_non_string_format_errors() {
+    // Arrange
+    let mut interpreter = Interpreter::new(parse("printf 1, 2;")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "printf format must be a string"
+    );
+}
+
+#[test]
+fn class_instance_fields_and_methods() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "class Counter {
+        increment() { this.count = this.count + 1; return this.count; }
+    }
+    var counter = Counter();
+    counter.count = 1;
+    counter.increment();
+    print counter.increment();
+    print counter;
+    print Counter;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(
+        output.contents(),
+        "3\n<Counter instance>\n<class Counter>\n"
+    );
+}
+
+#[test]
+fn bound_method_keeps_its_instance() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "class Greeter { greet() { print this.name; } }
+    var greeter = Greeter();
+    greeter.name = \"lox\";
+    var greet = greeter.greet;
+    greeter = nil;
+    greet();";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "lox\n");
+}
+
+#[test]
+fn undefined_property_errors() {
+    // Arrange
+    let mut interpreter =
+        Interpreter::new(parse("class A {}\nprint A().missing;")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(result.unwrap_err().message, "undefined property 'missing'");
+}
+
+#[test]
+fn property_on_non_instance_errors() {
+    // Arrange
+    let mut interpreter = Interpreter::new(parse("var a = 1;\na.b = 2;")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(result.unwrap_err().message, "only instances have fields");
+}
+
+#[test]
+fn print_function_mode_calls_native_print() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let tokens = Scanner::new("print(\"hi\");\nvar show = print;\nshow(1 + 2);")
+        .tokenize()
+        .unwrap();
+    let statements = Parser::new(tokens).with_print_function().parse().unwrap();
+    let mut interpreter = Interpreter::new(statements).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "hi\n3\n");
+}
+
+#[test]
+fn print_statement_mode_is_the_default() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let mut interpreter = Interpreter::new(parse("print \"hi\";")).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "hi\n");
+}
+
+#[test]
+fn native_print_checks_arity() {
+    // Arrange
+    let tokens = Scanner::new("print(1, 2);").tokenize().unwrap();
+    let statements = Parser::new(tokens).with_print_function().parse().unwrap();
+    let mut interpreter = Interpreter::new(statements).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "expected 1 arguments but got 2"
+    );
+}
+
+#[test]
+fn round_half_even_differs_from_round_on_ties() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print round(2.5);
+    print round_half_even(2.5, 0);
+    print round_half_even(3.5, 0);
+    print round_half_even(-2.5, 0);
+    print round_half_even(0.125, 2);";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "3\n2\n4\n-2\n0.12\n");
+}
+
+#[test]
+fn round_half_even_to_more_digits_than_a_number_holds_keeps_it() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print round_half_even(1.5, 400);
+    print round_half_even(0, 400);
+    print round_half_even(0.125, 20);
+    print round_half_even(123456789.5, 9);";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "1.5\n0\n0.125\n123456789.5\n");
+}
+
+#[test]
+fn round_half_even_rejects_invalid_arguments() {
+    // Arrange
+    let sources = ["round_half_even(\"1\", 0);", "round_half_even(1.5, -1);"];
+
+    // Act
+    let messages: Vec<String> = sources
+        .iter()
+        .map(|source| {
+            let mut interpreter = Interpreter::new(parse(source)).with_output(io::sink());
+            interpreter.interpret().unwrap_err().message
+        })
+        .collect();
+
+    // Assert
+    assert_eq!(
+        messages,
+        [
+            "expected a number but got string",
+            "digits must be a non-negative integer"
+        ]
+    );
+}
+
+#[test]
+fn init_populates_fields_from_arguments() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "class Point {
+        init(x, y) { this.x = x; this.y = y; }
+    }
+    var point = Point(1, 2);
+    print point.x + point.y;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "3\n");
+}
+
+#[test]
+fn init_returns_its_instance() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "class Box {
+        init(value) { this.value = value; if (value) return; this.value = 0; }
+    }
+    var box = Box(nil);
+    print box.init(5) == box;
+    print box.value;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "true\n5\n");
+}
+
+#[test]
+fn class_arity_follows_init() {
+    // Arrange
+    let mut interpreter =
+        Interpreter::new(parse("class A { init(a) {} }\nA();")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "expected 1 arguments but got 0"
+    );
+}
+
+const CYCLIC_CLOSURES: &str = "fun makeCycle() {
+    var f;
+    fun g() { return f; }
+    f = g;
+}
+var i = 0;
+while (i < 500) { makeCycle(); i = i + 1; }";
+
+#[test]
+fn cyclic_closures_stay_alive_without_gc() {
+    // Arrange
+    let mut interpreter = Interpreter::new(parse(CYCLIC_CLOSURES)).with_output(io::sink());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert!(interpreter.live_environments() >= 500);
+}
+
+#[test]
+fn gc_reclaims_cyclic_closures() {
+    // Arrange
+    let source = format!("{CYCLIC_CLOSURES}\ngc();");
+    let mut interpreter = Interpreter::new(parse(&source)).with_output(io::sink());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(interpreter.live_environments(), 1);
+}
+
+#[test]
+fn gc_keeps_reachable_closures_and_instances() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "fun makeCounter() {
+        var count = 0;
+        fun increment() { count = count + 1; return count; }
+        return increment;
+    }
+    class Holder { get() { return this.value; } }
+    var counter = makeCounter();
+    var holder = Holder();
+    holder.value = 7;
+    holder.self = holder;
+    counter();
+    gc();
+    print counter();
+    print holder.self.get();";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "2\n7\n");
+}
+
+#[test]
+fn gc_traces_long_chains_of_instances() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "class Node {}
+    var head = nil;
+    for (var i = 0; i < 100000; i = i + 1) {
+        var node = Node();
+        node.next = head;
+        head = node;
+    }
+    gc();
+    var length = 0;
+    while (head != nil) {
+        var next = head.next;
+        // Unlinked one by one, dropping the whole chain at once would recurse as deep as it is long
+        head.next = nil;
+        head = next;
+        length = length + 1;
+    }
+    print length;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "100000\n");
+}
+
+#[test]
+fn mixed_type_addition_names_operand_types() {
+    // Arrange
+    let sources = ["print 1 +\n\"a\";", "print \"a\" + 1;", "print nil + 1;"];
+
+    // Act
+    let errors: Vec<(String, usize)> = sources
+        .iter()
+        .map(|source| {
+            let mut interpreter = Interpreter::new(parse(source)).with_output(io::sink());
+            let error = interpreter.interpret().unwrap_err();
+            (error.message, error.context.line_number)
+        })
+        .collect();
+
+    // Assert
+    let expected = [
+        ("number", "string", 1),
+        ("string", "number", 1),
+        ("nil", "number", 1),
+    ]
+    .map(|(left, right, line)| {
+        (
+            format!("operands must be two numbers or two strings, got {left} and {right}"),
+            line,
+        )
+    });
+    assert_eq!(errors, expected);
+}
+
+#[test]
+fn gcd_and_lcm_of_integers() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print gcd(12, 18);
+    print lcm(4, 6);
+    print gcd(0, 0);
+    print gcd(-12, 18);
+    print lcm(0, 5);";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "6\n12\n0\n6\n0\n");
+}
+
+#[test]
+fn gcd_and_lcm_reject_invalid_arguments() {
+    // Arrange
+    let sources = [
+        "gcd(1.5, 2);",
+        "lcm(\"4\", 6);",
+        // 2^1000 and 3^30 are coprime, their product overflows
+        "var a = 1;\nfor (var i = 0; i < 1000; i = i + 1) a = a * 2;\nlcm(a, 205891132094649);",
+    ];
+
+    // Act
+    let messages: Vec<String> = sources
+        .iter()
+        .map(|source| {
+            let mut interpreter = Interpreter::new(parse(source)).with_output(io::sink());
+            interpreter.interpret().unwrap_err().message
+        })
+        .collect();
+
+    // Assert
+    assert_eq!(
+        messages,
+        [
+            "expected an integer but got 1.5",
+            "expected a number but got string",
+            "lcm is too large to represent"
+        ]
+    );
+}
+
+#[test]
+fn strings_compare_lexicographically() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print \"abc\" < \"abd\";
+    print \"b\" > \"abc\";
+    print \"abc\" <= \"abc\";
+    print \"abc\" >= \"abc\";
+    print \"abc\" < \"abc\";
+    print \"ab\" < \"abc\";";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "true\ntrue\ntrue\ntrue\nfalse\ntrue\n");
+}
+
+#[test]
+fn numbers_still_compare_numerically() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print 10 > 9;\nvar nan = 0 / 0;\nprint nan < 1;\nprint nan >= 1;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "true\nfalse\nfalse\n");
+}
+
+#[test]
+fn comparing_string_with_number_errors() {
+    // Arrange
+    let mut interpreter = Interpreter::new(parse("print \"1\" < 2;")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().message,
+        "operands must be two numbers or two strings, got string and number"
+    );
+}
+
+#[test]
+fn repeat_and_pad_strings() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print repeat(\"ab\", 3);
+    print repeat(\"ab\", 0) == \"\";
+    print pad_left(\"7\", 3, \"0\");
+    print pad_right(\"é\", 3, \".\");
+    print pad_left(\"long\", 2, \" \");";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "ababab\ntrue\n007\né..\nlong\n");
+}
+
+#[test]
+fn repeat_and_pad_reject_invalid_arguments() {
+    // Arrange
+    let sources = [
+        "repeat(\"ab\", -1);",
+        "repeat(1, 2);",
+        "pad_left(\"7\", 1.5, \"0\");",
+        "pad_right(\"7\", 3, \"00\");",
+        "pad_right(\"7\", 3, \"\");",
+        "repeat(\"ab\", 9007199254740992);",
+        "repeat(\"ab\", 1073741824);",
+        "pad_left(\"7\", 9007199254740992, \"0\");",
+    ];
+
+    // Act
+    let messages: Vec<String> = sources
+        .iter()
+        .map(|source| {
+            let mut interpreter = Interpreter::new(parse(source)).with_output(io::sink());
+            interpreter.interpret().unwrap_err().message
+        })
+        .collect();
+
+    // Assert
+    assert_eq!(
+        messages,
+        [
+            "count must be a non-negative integer",
+            "expected a string but got number",
+            "width must be a non-negative integer",
+            "fill must be a single character",
+            "fill must be a single character",
+            "resulting string is too large",
+            "resulting string is too large",
+            "resulting string is too large"
+        ]
+    );
+}
+
+#[test]
+fn failing_assert_reports_expression_text() {
+    // Arrange
+    let source = "var a = 1;\nvar b = 2;\nassert a == a;\nassert a == b;";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(io::sink());
+
+    // Act
+    let error = interpreter.interpret().unwrap_err();
+
+    // Assert
+    assert_eq!(error.message, "assertion failed: a == b");
+    assert_eq!(error.context.line_number, 4);
+}
+
+#[test]
+fn assert_message_keeps_adjacent_tokens_together() {
+    // Arrange
+    let source = "fun half(n) { return n / 2; }\nassert half(4)  >\n  (1 + 2);";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(io::sink());
+
+    // Act
+    let error = interpreter.interpret().unwrap_err();
+
+    // Assert
+    assert_eq!(error.message, "assertion failed: half(4) > (1 + 2)");
+}
+
+#[test]
+fn len_counts_string_characters() {
+    // Arrange
+    let output = SharedBuffer::default();
+    let source = "print len(\"lox\");\nprint len(\"\");\nprint len(\"héllo\");";
+    let mut interpreter = Interpreter::new(parse(source)).with_output(output.clone());
+
+    // Act
+    interpreter.interpret().unwrap();
+
+    // Assert
+    assert_eq!(output.contents(), "3\n0\n5\n");
+}
+
+#[test]
+fn len_of_non_string_errors() {
+    // Arrange
+    let mut interpreter = Interpreter::new(parse("len(42);")).with_output(io::sink());
+
+    // Act
+    let result = interpreter.interpret();
+
+    // Assert
+    let error = result.unwrap_err();
+    assert_eq!(error.message, "expected a string but got number");
+    assert_eq!(error.context.lexeme, ")");
+}
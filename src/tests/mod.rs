@@ -1,2 +1,3 @@
+mod interpreter_integration_tests;
 mod parser_integration_tests;
 mod scanner_integration_tests;
@@ -1,2 +1,513 @@
-#[cfg(test)]
-mod parser_integration_tests {}
+#![cfg(test)]
+
+use crate::ast::{Expr, Stmt};
+use crate::common::{Token, TokenType};
+use crate::parser::parser::Parser;
+use crate::parser::precedence::{Associativity, PrecedenceTable};
+use crate::scanner::Scanner;
+use std::rc::Rc;
+
+#[test]
+fn parse_with_recovery_returns_partial_ast_and_errors() {
+    // Arrange
+    let tokens = Scanner::new("print 1;\nprint +;\nprint 2;")
+        .tokenize()
+        .unwrap();
+
+    // Act
+    let (statements, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(statements.len(), 2);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn if_with_empty_then_and_non_empty_else_warns() {
+    // Arrange
+    let tokens = Scanner::new("if (c) {} else { print 1; }")
+        .tokenize()
+        .unwrap();
+    let mut parser = Parser::new(tokens);
+
+    // Act
+    let (_, errors) = parser.parse_with_recovery();
+
+    // Assert
+    assert!(errors.is_empty());
+    assert_eq!(parser.warnings().len(), 1);
+}
+
+#[test]
+fn if_with_non_empty_then_does_not_warn() {
+    // Arrange
+    let tokens = Scanner::new("if (c) { print 1; } else {}")
+        .tokenize()
+        .unwrap();
+    let mut parser = Parser::new(tokens);
+
+    // Act
+    parser.parse_with_recovery();
+
+    // Assert
+    assert!(parser.warnings().is_empty());
+}
+
+#[test]
+fn decorator_is_not_yet_supported() {
+    // Arrange
+    let tokens = Scanner::new("@memo\nprint 1;").tokenize().unwrap();
+
+    // Act
+    let (_, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(errors[0].message, "decorators are not yet supported");
+}
+
+#[test]
+fn error_names_expected_and_found_tokens() {
+    // Arrange
+    let tokens = Scanner::new("{ print 1 }").tokenize().unwrap();
+
+    // Act
+    let (_, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(errors[0].message, "expected ';' after value, but found '}'");
+}
+
+#[test]
+fn unterminated_block_points_at_opening_brace() {
+    // Arrange
+    let tokens = Scanner::new("print 0;\n{\nprint 1;").tokenize().unwrap();
+
+    // Act
+    let (_, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(errors[0].message, "unterminated block; expected '}'");
+    assert_eq!(errors[0].context.line_number, 2);
+    assert_eq!(errors[0].context.lexeme, "{");
+}
+
+#[test]
+fn unterminated_block_with_incomplete_statement_points_at_opening_brace() {
+    // Arrange
+    let tokens = Scanner::new("print 0;\n{\nprint 1").tokenize().unwrap();
+
+    // Act
+    let (statements, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(statements.len(), 1);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "unterminated block; expected '}'");
+    assert_eq!(errors[0].context.line_number, 2);
+}
+
+#[test]
+fn bracket_error_does_not_hide_other_statements_errors() {
+    // Arrange
+    let tokens = Scanner::new("print 1);\nprint 2;\nvar;")
+        .tokenize()
+        .unwrap();
+
+    // Act
+    let (statements, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(statements.len(), 1);
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "unexpected ')' with no matching '('",
+            "expected variable name, but found ';'"
+        ]
+    );
+}
+
+#[test]
+fn loop_with_empty_statement_body_warns() {
+    // Arrange
+    let tokens = Scanner::new("while (x) ;").tokenize().unwrap();
+    let mut parser = Parser::new(tokens);
+
+    // Act
+    let (_, errors) = parser.parse_with_recovery();
+
+    // Assert
+    assert!(errors.is_empty());
+    assert_eq!(parser.warnings().len(), 1);
+}
+
+#[test]
+fn loop_with_empty_block_body_does_not_warn() {
+    // Arrange
+    let tokens = Scanner::new("while (x) { }").tokenize().unwrap();
+    let mut parser = Parser::new(tokens);
+
+    // Act
+    parser.parse_with_recovery();
+
+    // Assert
+    assert!(parser.warnings().is_empty());
+}
+
+/// Renders binary and logical expressions as fully parenthesized prefix notation, e.g. `(+ 1 2)`.
+fn to_prefix(expr: &Expr) -> String {
+    match expr {
+        Expr::Binary {
+            left,
+            operator,
+            right,
+        }
+        | Expr::Logical {
+            left,
+            operator,
+            right,
+        } => format!(
+            "({} {} {})",
+            operator.lexeme,
+            to_prefix(left),
+            to_prefix(right)
+        ),
+        Expr::Unary { operator, right } => format!("({} {})", operator.lexeme, to_prefix(right)),
+        Expr::Grouping { expression } => to_prefix(expression),
+        Expr::Variable { name, .. } => name.lexeme.clone(),
+        _ => panic!("Unexpected expression."),
+    }
+}
+
+fn parse_expression(source: &str, precedence: PrecedenceTable) -> String {
+    let tokens = Scanner::new(source).tokenize().unwrap();
+    let statements = Parser::new(tokens)
+        .with_precedence(precedence)
+        .parse()
+        .unwrap();
+
+    match &statements[0] {
+        Stmt::Expression { expression } => to_prefix(expression),
+        _ => panic!("Expected expression statement."),
+    }
+}
+
+#[test]
+fn default_precedence_matches_lox_grammar() {
+    let cases = [
+        ("a + b * c;", "(+ a (* b c))"),
+        ("a - b - c;", "(- (- a b) c)"),
+        ("a / b * c;", "(* (/ a b) c)"),
+        ("-a * b;", "(* (- a) b)"),
+        ("a < b == c >= d;", "(== (< a b) (>= c d))"),
+        ("a or b and c == d;", "(or a (and b (== c d)))"),
+        ("a xor b or c;", "(or (xor a b) c)"),
+        ("(a + b) * c;", "(* (+ a b) c)"),
+    ];
+
+    for (source, expected) in cases {
+        // Act
+        let actual = parse_expression(source, PrecedenceTable::default());
+
+        // Assert
+        assert_eq!(actual, expected, "parsing {source}");
+    }
+}
+
+#[test]
+fn overridden_precedence_changes_grouping() {
+    // Arrange
+    let precedence =
+        PrecedenceTable::default().with_operator(TokenType::Plus, 10, Associativity::Left);
+
+    // Act
+    let actual = parse_expression("a * b + c;", precedence);
+
+    // Assert
+    assert_eq!(actual, "(* a (+ b c))");
+}
+
+#[test]
+fn right_associative_operator_groups_to_the_right() {
+    // Arrange
+    let precedence =
+        PrecedenceTable::default().with_operator(TokenType::Minus, 5, Associativity::Right);
+
+    // Act
+    let actual = parse_expression("a - b - c;", precedence);
+
+    // Assert
+    assert_eq!(actual, "(- a (- b c))");
+}
+
+#[test]
+fn modulo_binds_like_multiplication() {
+    // Act
+    let actual = parse_expression("a + b % c * d;", PrecedenceTable::default());
+
+    // Assert
+    assert_eq!(actual, "(+ a (* (% b c) d))");
+}
+
+fn parse_function(source: &str) -> (Token, Vec<Token>, Rc<[Stmt]>) {
+    let tokens = Scanner::new(source).tokenize().unwrap();
+    let mut statements = Parser::new(tokens).parse().unwrap();
+
+    match statements.remove(0) {
+        Stmt::Function {
+            name,
+            parameters,
+            body,
+        } => (name, parameters, body),
+        _ => panic!("Expected function declaration."),
+    }
+}
+
+#[test]
+fn function_without_parameters() {
+    // Act
+    let (name, parameters, body) = parse_function("fun hello() { print 1; }");
+
+    // Assert
+    assert_eq!(name.lexeme, "hello");
+    assert!(parameters.is_empty());
+    assert_eq!(body.len(), 1);
+}
+
+#[test]
+fn function_with_one_parameter() {
+    // Act
+    let (_, parameters, body) = parse_function("fun id(x) {}");
+
+    // Assert
+    let names: Vec<&str> = parameters.iter().map(|p| p.lexeme.as_str()).collect();
+    assert_eq!(names, ["x"]);
+    assert!(body.is_empty());
+}
+
+#[test]
+fn function_with_many_parameters() {
+    // Act
+    let (_, parameters, _) = parse_function("fun sum(a, b, c, d) { print a + b + c + d; }");
+
+    // Assert
+    let names: Vec<&str> = parameters.iter().map(|p| p.lexeme.as_str()).collect();
+    assert_eq!(names, ["a", "b", "c", "d"]);
+}
+
+#[test]
+fn function_with_more_than_255_parameters_errors() {
+    // Arrange
+    let parameters: Vec<String> = (0..256).map(|i| format!("p{i}")).collect();
+    let source = format!("fun f({}) {{}}", parameters.join(", "));
+    let tokens = Scanner::new(source).tokenize().unwrap();
+
+    // Act
+    let (_, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "can't have more than 255 parameters");
+    assert_eq!(errors[0].context.lexeme, "p255");
+}
+
+fn parse_call(source: &str) -> Expr {
+    let tokens = Scanner::new(source).tokenize().unwrap();
+    let mut statements = Parser::new(tokens).parse().unwrap();
+
+    match statements.remove(0) {
+        Stmt::Expression { expression } => *expression,
+        _ => panic!("Expected expression statement."),
+    }
+}
+
+#[test]
+fn call_without_arguments() {
+    // Act
+    let expr = parse_call("f();");
+
+    // Assert
+    let Expr::Call {
+        callee,
+        paren,
+        arguments,
+    } = expr
+    else {
+        panic!("Expected call expression.");
+    };
+    assert_eq!(to_prefix(&callee), "f");
+    assert_eq!(paren.token_type, TokenType::RightParen);
+    assert!(arguments.is_empty());
+}
+
+#[test]
+fn call_with_many_arguments() {
+    // Act
+    let expr = parse_call("f(a, b + c, d);");
+
+    // Assert
+    let Expr::Call { arguments, .. } = expr else {
+        panic!("Expected call expression.");
+    };
+    let arguments: Vec<String> = arguments.iter().map(to_prefix).collect();
+    assert_eq!(arguments, ["a", "(+ b c)", "d"]);
+}
+
+#[test]
+fn chained_calls() {
+    // Act
+    let expr = parse_call("f(a)(b);");
+
+    // Assert
+    let Expr::Call {
+        callee, arguments, ..
+    } = expr
+    else {
+        panic!("Expected call expression.");
+    };
+    assert_eq!(to_prefix(&arguments[0]), "b");
+    let Expr::Call {
+        callee, arguments, ..
+    } = *callee
+    else {
+        panic!("Expected inner call expression.");
+    };
+    assert_eq!(to_prefix(&callee), "f");
+    assert_eq!(to_prefix(&arguments[0]), "a");
+}
+
+#[test]
+fn call_with_more_than_255_arguments_errors() {
+    // Arrange
+    let arguments = vec!["1"; 256].join(", ");
+    let tokens = Scanner::new(format!("f({arguments});")).tokenize().unwrap();
+
+    // Act
+    let (_, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "can't have more than 255 arguments");
+}
+
+#[test]
+fn return_outside_function_errors() {
+    // Arrange
+    let tokens = Scanner::new("fun f() { return 1; }\nreturn 2;")
+        .tokenize()
+        .unwrap();
+
+    // Act
+    let (statements, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(statements.len(), 1);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "can't return from top-level code");
+}
+
+#[test]
+fn stray_closing_paren_reports_missing_opener() {
+    // Arrange
+    let tokens = Scanner::new("print 1;\nprint 2);\nprint 3;")
+        .tokenize()
+        .unwrap();
+
+    // Act
+    let (statements, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(statements.len(), 2);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "unexpected ')' with no matching '('");
+    assert_eq!(errors[0].context.line_number, 2);
+    assert_eq!(errors[0].context.column, 8);
+}
+
+#[test]
+fn unclosed_paren_points_at_opener() {
+    // Arrange
+    let tokens = Scanner::new("print (1 + 2;\nprint 3;").tokenize().unwrap();
+
+    // Act
+    let (statements, errors) = Parser::new(tokens).parse_with_recovery();
+
+    // Assert
+    assert_eq!(statements.len(), 1);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "unclosed '('; expected ')'");
+    assert_eq!(errors[0].context.line_number, 1);
+    assert_eq!(errors[0].context.column, 7);
+}
+
+#[test]
+fn class_declaration_collects_methods() {
+    // Arrange
+    let tokens = Scanner::new("class Point { x() { return 1; } y() {} }")
+        .tokenize()
+        .unwrap();
+
+    // Act
+    let mut statements = Parser::new(tokens).parse().unwrap();
+
+    // Assert
+    let Stmt::Class { name, methods } = statements.remove(0) else {
+        panic!("Expected class declaration.");
+    };
+    assert_eq!(name.lexeme, "Point");
+    assert_eq!(methods.len(), 2);
+}
+
+#[test]
+fn property_assignment_becomes_set() {
+    // Act
+    let expr = parse_call("a.b.c = d;");
+
+    // Assert
+    let Expr::Set {
+        object,
+        name,
+        value,
+    } = expr
+    else {
+        panic!("Expected set expression.");
+    };
+    assert_eq!(name.lexeme, "c");
+    assert_eq!(to_prefix(&value), "d");
+    let Expr::Get { object, name } = *object else {
+        panic!("Expected get expression.");
+    };
+    assert_eq!(name.lexeme, "b");
+    assert_eq!(to_prefix(&object), "a");
+}
+
+#[test]
+fn self_assignment_warns() {
+    // Arrange
+    let tokens = Scanner::new("var x = 1;\nx = x;").tokenize().unwrap();
+    let mut parser = Parser::new(tokens);
+
+    // Act
+    let (_, errors) = parser.parse_with_recovery();
+
+    // Assert
+    assert!(errors.is_empty());
+    assert_eq!(parser.warnings().len(), 1);
+    assert_eq!(parser.warnings()[0].context.line_number, 2);
+}
+
+#[test]
+fn assignment_using_own_value_does_not_warn() {
+    // Arrange
+    let tokens = Scanner::new("var x = 1;\nx = x + 1;\nx = y;")
+        .tokenize()
+        .unwrap();
+    let mut parser = Parser::new(tokens);
+
+    // Act
+    parser.parse_with_recovery();
+
+    // Assert
+    assert!(parser.warnings().is_empty());
+}
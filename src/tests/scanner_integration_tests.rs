@@ -1,17 +1,95 @@
-#[cfg(test)]
-mod scanner_integration_tests {
-    use crate::common::TokenType;
-    use crate::scanner::Scanner;
+#![cfg(test)]
 
-    #[test]
-    fn test_scanner_basic() {
-        // Arrange
-        let scanner = Scanner::new("");
+use crate::common::TokenType;
+use crate::scanner::Scanner;
 
-        // Act
-        let tokens = scanner.tokenize().unwrap();
+#[test]
+fn test_scanner_basic() {
+    // Arrange
+    let scanner = Scanner::new("");
 
-        // Assert
-        assert_eq!(tokens.last().unwrap().token_type, TokenType::Eof);
+    // Act
+    let tokens = scanner.tokenize().unwrap();
+
+    // Assert
+    assert_eq!(tokens.last().unwrap().token_type, TokenType::Eof);
+}
+
+#[test]
+fn emit_tokens_json() {
+    // Arrange
+    let scanner = Scanner::new("var greeting = \"hi\";");
+
+    // Act
+    let tokens = scanner.tokenize().unwrap();
+    let lines: Vec<String> = tokens.iter().map(|t| t.to_json()).collect();
+
+    // Assert
+    assert_eq!(
+        lines[0],
+        r#"{"type":"Var","lexeme":"var","line":1,"column":1,"literal":null}"#
+    );
+    assert_eq!(
+        lines[3],
+        r#"{"type":"String","lexeme":"\"hi\"","line":1,"column":16,"literal":"hi"}"#
+    );
+}
+
+#[test]
+fn scan_multibyte_characters() {
+    // Arrange
+    let scanner = Scanner::new("var café = \"naïve ☕\";");
+
+    // Act
+    let tokens = scanner.tokenize();
+
+    // Assert
+    // Identifiers are ASCII only, so `é` is rejected, but it must not break slicing
+    assert!(tokens.is_none());
+
+    let tokens = Scanner::new("print \"naïve ☕\" + r\"日本\";")
+        .tokenize()
+        .unwrap();
+    let lexemes: Vec<&str> = tokens.iter().map(|t| t.lexeme.as_str()).collect();
+    assert_eq!(
+        lexemes,
+        ["print", "\"naïve ☕\"", "+", "r\"日本\"", ";", ""]
+    );
+}
+
+#[test]
+fn scan_long_source_matches_per_line_scan() {
+    // Arrange
+    let snippet = [
+        "var pi = 3.14159; // ☕ comment",
+        "while (i <= 100) { i = i + 1; }",
+        "print \"héllo ${name}!\" + r\"raw ✓\";",
+        "/* block ∑ */ if (a != b) print a;",
+    ];
+    let expected: Vec<_> = snippet
+        .iter()
+        .map(|line| Scanner::new(*line).tokenize().unwrap())
+        .collect();
+    let line_count = 5000;
+    let source: String = (0..line_count)
+        .map(|i| snippet[i % snippet.len()])
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Act
+    let tokens = Scanner::new(source).tokenize().unwrap();
+
+    // Assert
+    let mut tokens = tokens.iter();
+    for i in 0..line_count {
+        let line_tokens = &expected[i % snippet.len()];
+        for token in &line_tokens[..line_tokens.len() - 1] {
+            let actual = tokens.next().unwrap();
+            assert_eq!(actual.token_type, token.token_type);
+            assert_eq!(actual.lexeme, token.lexeme);
+            assert_eq!(actual.literal, token.literal);
+            assert_eq!(actual.line, i + 1);
+        }
     }
+    assert_eq!(tokens.next().unwrap().token_type, TokenType::Eof);
 }